@@ -1,4 +1,4 @@
-use sonor::Speaker;
+use sonor::{CurrentTrack, Speaker};
 use std::time::Duration;
 
 type Result<T, E = sonor::Error> = std::result::Result<T, E>;
@@ -29,19 +29,25 @@ async fn general(speaker: &Speaker) -> Result<()> {
 async fn currently_playing(speaker: &Speaker) -> Result<()> {
     println!();
 
-    let track_info = speaker.track().await?;
-    if let Some(track_info) = track_info {
-        let duration = fmt_duration(track_info.duration());
-        let elapsed = fmt_duration(track_info.elapsed());
-        println!(
-            "Currently playing: '{}' [{}/{}]",
-            track_info.track(),
-            elapsed,
-            duration
-        );
-    } else {
-        println!("No track are currently playing...");
-        return Ok(());
+    match speaker.track().await? {
+        Some(CurrentTrack::Track(track_info)) => {
+            let duration = fmt_duration(track_info.duration());
+            let elapsed = fmt_duration(track_info.elapsed());
+            println!(
+                "Currently playing: '{}' [{}/{}]",
+                track_info.track(),
+                elapsed,
+                duration
+            );
+        }
+        Some(CurrentTrack::Tv) => {
+            println!("Currently playing the TV");
+            return Ok(());
+        }
+        None => {
+            println!("No track are currently playing...");
+            return Ok(());
+        }
     }
 
     let queue = &speaker.queue().await?;
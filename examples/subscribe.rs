@@ -0,0 +1,32 @@
+use futures::prelude::*;
+use sonor::{Event, Service};
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() -> Result<(), sonor::Error> {
+    let roomname = std::env::args()
+        .nth(1)
+        .expect("expected room name as first argument");
+
+    let speaker = sonor::find(&roomname, Duration::from_secs(3))
+        .await?
+        .unwrap_or_else(|| panic!("speaker '{}' doesn't exist", roomname));
+
+    let mut events = speaker.subscribe(Service::RenderingControl).await?;
+
+    while let Some(event) = events.try_next().await? {
+        match event {
+            Event::RenderingControl(change) => {
+                if let Some(volume) = change.volume() {
+                    println!("Volume: {}", volume);
+                }
+                if let Some(mute) = change.mute() {
+                    println!("Mute: {}", mute);
+                }
+            }
+            event => println!("{:?}", event),
+        }
+    }
+
+    Ok(())
+}
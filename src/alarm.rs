@@ -0,0 +1,449 @@
+use crate::{datatypes, utils, Error, RepeatMode, Result};
+use roxmltree::{Document, Node};
+use std::{fmt, ops};
+
+/// This enum describes on which days an [Alarm](struct.Alarm.html) goes off.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recurrence {
+    /// The alarm goes off once and gets disabled afterwards.
+    Once,
+    /// Monday to Friday.
+    Weekdays,
+    /// Saturday and Sunday.
+    Weekends,
+    /// Every day.
+    Daily,
+    /// A custom set of days.
+    On(Days),
+}
+
+/// A non-empty set of days of the week for [Recurrence::On](enum.Recurrence.html#variant.On).
+///
+/// Sets can be combined using `|`, e.g. `Days::MONDAY | Days::THURSDAY`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Days(u8);
+
+#[allow(missing_docs)]
+impl Days {
+    pub const SUNDAY: Self = Self(1);
+    pub const MONDAY: Self = Self(1 << 1);
+    pub const TUESDAY: Self = Self(1 << 2);
+    pub const WEDNESDAY: Self = Self(1 << 3);
+    pub const THURSDAY: Self = Self(1 << 4);
+    pub const FRIDAY: Self = Self(1 << 5);
+    pub const SATURDAY: Self = Self(1 << 6);
+
+    /// Returns the day with the number `day`, where `0` is Sunday and `6` is Saturday.
+    pub fn from_day(day: u8) -> Option<Self> {
+        if day <= 6 {
+            Some(Self(1 << day))
+        } else {
+            None
+        }
+    }
+    /// Whether all days in `other` are part of this set.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    pub fn insert(&mut self, other: Self) -> &mut Self {
+        self.0 |= other.0;
+        self
+    }
+    /// The numbers of the days in the set, starting with Sunday as `0`.
+    pub fn days(self) -> impl Iterator<Item = u8> {
+        (0..=6).filter(move |day| self.0 & (1 << day) != 0)
+    }
+}
+
+impl ops::BitOr for Days {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl ops::BitOrAssign for Days {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Once => f.write_str("ONCE"),
+            Recurrence::Weekdays => f.write_str("WEEKDAYS"),
+            Recurrence::Weekends => f.write_str("WEEKENDS"),
+            Recurrence::Daily => f.write_str("DAILY"),
+            Recurrence::On(days) => {
+                f.write_str("ON_")?;
+                days.days().try_for_each(|day| write!(f, "{}", day))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseRecurrenceError;
+impl std::error::Error for ParseRecurrenceError {}
+impl fmt::Display for ParseRecurrenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "provided string was not `ONCE`, `WEEKDAYS`, `WEEKENDS`, `DAILY` or `ON_<days>`".fmt(f)
+    }
+}
+
+impl std::str::FromStr for Recurrence {
+    type Err = ParseRecurrenceError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "ONCE" => Ok(Recurrence::Once),
+            "WEEKDAYS" => Ok(Recurrence::Weekdays),
+            "WEEKENDS" => Ok(Recurrence::Weekends),
+            "DAILY" => Ok(Recurrence::Daily),
+            s => {
+                let days = s.strip_prefix("ON_").ok_or(ParseRecurrenceError)?;
+                let mut set: Option<Days> = None;
+                for day in days.chars() {
+                    let day = day
+                        .to_digit(10)
+                        .and_then(|day| Days::from_day(day as u8))
+                        .ok_or(ParseRecurrenceError)?;
+                    *set.get_or_insert(day) |= day;
+                }
+                set.map(Recurrence::On).ok_or(ParseRecurrenceError)
+            }
+        }
+    }
+}
+
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
+fn check_start_time(start_time: u32) -> Result<()> {
+    if start_time >= SECONDS_PER_DAY {
+        return Err(Error::ValueOutOfRange {
+            name: "StartTime".to_string(),
+            value: start_time.min(i32::MAX as u32) as i32,
+            min: 0,
+            max: SECONDS_PER_DAY as i32 - 1,
+        });
+    }
+    Ok(())
+}
+
+/// An alarm of the sonos system.
+/// Alarms are shared by all speakers in the household and play in the room given by the
+/// [room_uuid](struct.Alarm.html#method.room_uuid).
+///
+/// They are listed with [Speaker::alarms](struct.Speaker.html#method.alarms) and created with
+/// [Speaker::create_alarm](struct.Speaker.html#method.create_alarm).
+#[derive(Debug, Clone)]
+pub struct Alarm {
+    id: Option<u32>,
+    start_time: u32,
+    duration: u32,
+    recurrence: Recurrence,
+    enabled: bool,
+    room_uuid: String,
+    program_uri: String,
+    program_metadata: String,
+    repeat_mode: RepeatMode,
+    shuffle: bool,
+    volume: u16,
+    include_linked_zones: bool,
+}
+
+#[allow(missing_docs)]
+impl Alarm {
+    /// Creates a new alarm for the room with the UUID `room_uuid` going off `start_time`
+    /// seconds after midnight.
+    /// It plays the sonos chime daily at volume 20 for an hour.
+    ///
+    /// Returns `Error::ValueOutOfRange` if `start_time` isn't within a day.
+    pub fn new(room_uuid: impl Into<String>, start_time: u32) -> Result<Self> {
+        check_start_time(start_time)?;
+        Ok(Self {
+            id: None,
+            start_time,
+            duration: 3600,
+            recurrence: Recurrence::Daily,
+            enabled: true,
+            room_uuid: room_uuid.into(),
+            program_uri: "x-rincon-buzzer:0".to_string(),
+            program_metadata: String::new(),
+            repeat_mode: RepeatMode::None,
+            shuffle: false,
+            volume: 20,
+            include_linked_zones: false,
+        })
+    }
+
+    pub(crate) fn from_xml(node: Node<'_, '_>) -> Result<Self> {
+        let attr = |name| utils::find_node_attribute(node, name);
+
+        let (repeat_mode, shuffle) = datatypes::parse_play_mode(attr("PlayMode")?)?;
+
+        Ok(Self {
+            id: Some(
+                attr("ID")?
+                    .parse()
+                    .map_err(rupnp::Error::invalid_response)?,
+            ),
+            start_time: utils::seconds_from_str(attr("StartTime")?)?,
+            duration: utils::seconds_from_str(attr("Duration")?)?,
+            recurrence: attr("Recurrence")?
+                .parse()
+                .map_err(rupnp::Error::invalid_response)?,
+            enabled: utils::parse_bool(attr("Enabled")?)?,
+            room_uuid: attr("RoomUUID")?.to_string(),
+            program_uri: attr("ProgramURI")?.to_string(),
+            program_metadata: attr("ProgramMetaData")?.to_string(),
+            repeat_mode,
+            shuffle,
+            volume: attr("Volume")?
+                .parse()
+                .map_err(rupnp::Error::invalid_response)?,
+            include_linked_zones: utils::parse_bool(attr("IncludeLinkedZones")?)?,
+        })
+    }
+
+    pub(crate) fn list_from_xml(alarm_list: &str) -> Result<Vec<Self>> {
+        let doc = Document::parse(alarm_list)?;
+        utils::find_root_node(&doc, "Alarms", "Alarm List")?
+            .children()
+            .filter(Node::is_element)
+            .filter(|c| c.tag_name().name().eq_ignore_ascii_case("Alarm"))
+            .map(Alarm::from_xml)
+            .collect()
+    }
+
+    /// The arguments shared by the `CreateAlarm` and `UpdateAlarm` actions.
+    pub(crate) fn args(&self) -> String {
+        crate::args! {
+            "StartLocalTime": utils::seconds_to_str(self.start_time.into()),
+            "Duration": utils::seconds_to_str(self.duration.into()),
+            "Recurrence": self.recurrence,
+            "Enabled": self.enabled as u8,
+            "RoomUUID": self.room_uuid,
+            "ProgramURI": utils::escape(&self.program_uri),
+            "ProgramMetaData": utils::escape(&self.program_metadata),
+            "PlayMode": datatypes::play_mode_str(self.repeat_mode, self.shuffle),
+            "Volume": self.volume,
+            "IncludeLinkedZones": self.include_linked_zones as u8
+        }
+        .to_string()
+    }
+
+    /// Only alarms returned by [Speaker::alarms](struct.Speaker.html#method.alarms) have an ID.
+    pub fn id(&self) -> Option<u32> {
+        self.id
+    }
+    /// The start time in seconds after midnight (speaker local time).
+    pub fn start_time(&self) -> u32 {
+        self.start_time
+    }
+    /// How long the alarm plays in seconds.
+    pub fn duration(&self) -> u32 {
+        self.duration
+    }
+    pub fn recurrence(&self) -> &Recurrence {
+        &self.recurrence
+    }
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+    pub fn room_uuid(&self) -> &str {
+        &self.room_uuid
+    }
+    pub fn program_uri(&self) -> &str {
+        &self.program_uri
+    }
+    pub fn program_metadata(&self) -> &str {
+        &self.program_metadata
+    }
+    pub fn repeat_mode(&self) -> RepeatMode {
+        self.repeat_mode
+    }
+    pub fn shuffle(&self) -> bool {
+        self.shuffle
+    }
+    pub fn volume(&self) -> u16 {
+        self.volume
+    }
+    pub fn include_linked_zones(&self) -> bool {
+        self.include_linked_zones
+    }
+
+    /// Returns `Error::ValueOutOfRange` if `start_time` isn't within a day.
+    pub fn set_start_time(&mut self, start_time: u32) -> Result<&mut Self> {
+        check_start_time(start_time)?;
+        self.start_time = start_time;
+        Ok(self)
+    }
+    pub fn set_duration(&mut self, duration: u32) -> &mut Self {
+        self.duration = duration;
+        self
+    }
+    pub fn set_recurrence(&mut self, recurrence: Recurrence) -> &mut Self {
+        self.recurrence = recurrence;
+        self
+    }
+    pub fn set_enabled(&mut self, enabled: bool) -> &mut Self {
+        self.enabled = enabled;
+        self
+    }
+    pub fn set_room_uuid(&mut self, room_uuid: impl Into<String>) -> &mut Self {
+        self.room_uuid = room_uuid.into();
+        self
+    }
+    /// Sets what the alarm plays, e.g. a radio station or sonos playlist.
+    pub fn set_program(
+        &mut self,
+        program_uri: impl Into<String>,
+        program_metadata: impl Into<String>,
+    ) -> &mut Self {
+        self.program_uri = program_uri.into();
+        self.program_metadata = program_metadata.into();
+        self
+    }
+    pub fn set_repeat_mode(&mut self, repeat_mode: RepeatMode) -> &mut Self {
+        self.repeat_mode = repeat_mode;
+        self
+    }
+    pub fn set_shuffle(&mut self, shuffle: bool) -> &mut Self {
+        self.shuffle = shuffle;
+        self
+    }
+    pub fn set_volume(&mut self, volume: u16) -> &mut Self {
+        self.volume = volume;
+        self
+    }
+    pub fn set_include_linked_zones(&mut self, include_linked_zones: bool) -> &mut Self {
+        self.include_linked_zones = include_linked_zones;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recurrence_round_trip() {
+        for s in &[
+            "ONCE",
+            "WEEKDAYS",
+            "WEEKENDS",
+            "DAILY",
+            "ON_0",
+            "ON_135",
+            "ON_0123456",
+        ] {
+            assert_eq!(s.parse::<Recurrence>().unwrap().to_string(), *s);
+        }
+        assert_eq!(
+            "ON_51".parse::<Recurrence>().unwrap(),
+            Recurrence::On(Days::MONDAY | Days::FRIDAY)
+        );
+        assert_eq!(
+            Recurrence::On(Days::SATURDAY | Days::SUNDAY).to_string(),
+            "ON_06"
+        );
+    }
+
+    #[test]
+    fn invalid_recurrence() {
+        for s in &["", "ON_", "ON_7", "ON_19", "ON_1a", "NEVER"] {
+            assert!(s.parse::<Recurrence>().is_err(), "{} was accepted", s);
+        }
+        assert_eq!(Days::from_day(7), None);
+    }
+
+    const ALARM_LIST: &str = concat!(
+        r#"<Alarms>"#,
+        r#"<Alarm ID="14" StartTime="07:00:00" Duration="02:00:00" Recurrence="ON_135" "#,
+        r#"Enabled="1" RoomUUID="RINCON_000E58000000001400" ProgramURI="x-rincon-buzzer:0" "#,
+        r#"ProgramMetaData="" PlayMode="SHUFFLE_NOREPEAT" Volume="25" IncludeLinkedZones="0"/>"#,
+        r#"<Alarm ID="15" StartTime="22:30:00" Duration="00:30:00" Recurrence="DAILY" "#,
+        r#"Enabled="0" RoomUUID="RINCON_000E58000000002400" "#,
+        r#"ProgramURI="x-sonosapi-stream:s24940?sid=254&amp;flags=8224&amp;sn=0" "#,
+        r#"ProgramMetaData="&lt;DIDL-Lite xmlns:dc=&quot;http://purl.org/dc/elements/1.1/&quot; "#,
+        r#"xmlns=&quot;urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/&quot;&gt;&lt;item id=&quot;-1&quot; "#,
+        r#"parentID=&quot;-1&quot; restricted=&quot;true&quot;&gt;&lt;dc:title&gt;BBC Radio 6 Music"#,
+        r#"&lt;/dc:title&gt;&lt;/item&gt;&lt;/DIDL-Lite&gt;" "#,
+        r#"PlayMode="NORMAL" Volume="12" IncludeLinkedZones="1"/>"#,
+        r#"</Alarms>"#
+    );
+
+    #[test]
+    fn alarm_list() {
+        let alarms = Alarm::list_from_xml(ALARM_LIST).unwrap();
+        assert_eq!(alarms.len(), 2);
+
+        let wake_up = &alarms[0];
+        assert_eq!(wake_up.id(), Some(14));
+        assert_eq!(wake_up.start_time(), 7 * 3600);
+        assert_eq!(wake_up.duration(), 2 * 3600);
+        assert_eq!(
+            wake_up.recurrence(),
+            &Recurrence::On(Days::MONDAY | Days::WEDNESDAY | Days::FRIDAY)
+        );
+        assert!(wake_up.enabled());
+        assert_eq!(wake_up.room_uuid(), "RINCON_000E58000000001400");
+        assert_eq!(wake_up.program_uri(), "x-rincon-buzzer:0");
+        assert_eq!(wake_up.repeat_mode(), RepeatMode::None);
+        assert!(wake_up.shuffle());
+        assert_eq!(wake_up.volume(), 25);
+        assert!(!wake_up.include_linked_zones());
+
+        let radio = &alarms[1];
+        assert_eq!(radio.id(), Some(15));
+        assert!(!radio.enabled());
+        assert_eq!(
+            radio.program_uri(),
+            "x-sonosapi-stream:s24940?sid=254&flags=8224&sn=0"
+        );
+        assert!(radio.program_metadata().starts_with("<DIDL-Lite"));
+        assert!(!radio.shuffle());
+        assert!(radio.include_linked_zones());
+    }
+
+    #[test]
+    fn alarm_args() {
+        let alarms = Alarm::list_from_xml(ALARM_LIST).unwrap();
+
+        assert_eq!(
+            alarms[0].args(),
+            concat!(
+                "<StartLocalTime>07:00:00</StartLocalTime>",
+                "<Duration>02:00:00</Duration>",
+                "<Recurrence>ON_135</Recurrence>",
+                "<Enabled>1</Enabled>",
+                "<RoomUUID>RINCON_000E58000000001400</RoomUUID>",
+                "<ProgramURI>x-rincon-buzzer:0</ProgramURI>",
+                "<ProgramMetaData></ProgramMetaData>",
+                "<PlayMode>SHUFFLE_NOREPEAT</PlayMode>",
+                "<Volume>25</Volume>",
+                "<IncludeLinkedZones>0</IncludeLinkedZones>"
+            )
+        );
+
+        let args = alarms[1].args();
+        assert!(args.contains(
+            "<ProgramURI>x-sonosapi-stream:s24940?sid=254&amp;flags=8224&amp;sn=0</ProgramURI>"
+        ));
+        assert!(args.contains("<ProgramMetaData>&lt;DIDL-Lite xmlns:dc=&quot;"));
+        assert!(args.contains("<Recurrence>DAILY</Recurrence>"));
+    }
+
+    #[test]
+    fn start_time_within_a_day() {
+        assert!(Alarm::new("RINCON_000E58000000001400", 86399).is_ok());
+        assert!(Alarm::new("RINCON_000E58000000001400", 86400).is_err());
+
+        let mut alarm = Alarm::new("RINCON_000E58000000001400", 7 * 3600).unwrap();
+        assert!(alarm.set_start_time(u32::MAX).is_err());
+        assert_eq!(alarm.start_time(), 7 * 3600);
+    }
+}
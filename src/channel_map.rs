@@ -0,0 +1,305 @@
+use crate::SpeakerInfo;
+use std::fmt;
+
+/// The role a speaker takes in a bonded setup like a stereo pair or a home theater.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelRole {
+    /// Left front
+    LF,
+    /// Right front
+    RF,
+    /// Subwoofer
+    SW,
+    /// Left rear
+    LR,
+    /// Right rear
+    RR,
+}
+
+impl fmt::Display for ChannelRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseChannelMapError;
+impl std::error::Error for ParseChannelMapError {}
+impl fmt::Display for ParseChannelMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "provided string was not a channel map like `RINCON_A:LF,LF;RINCON_B:RF,RF`".fmt(f)
+    }
+}
+
+impl std::str::FromStr for ChannelRole {
+    type Err = ParseChannelMapError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "LF" => Ok(ChannelRole::LF),
+            "RF" => Ok(ChannelRole::RF),
+            "SW" => Ok(ChannelRole::SW),
+            "LR" => Ok(ChannelRole::LR),
+            "RR" => Ok(ChannelRole::RR),
+            _ => Err(ParseChannelMapError),
+        }
+    }
+}
+
+/// Describes which speaker plays which channels in a bonded setup.
+/// Its string representation is the `ChannelMapSet` used by sonos, e.g.
+/// `RINCON_A:LF,LF;RINCON_B:RF,RF` for a stereo pair.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChannelMap {
+    entries: Vec<(String, Vec<ChannelRole>)>,
+}
+
+impl ChannelMap {
+    /// Creates an empty channel map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The channel map of a stereo pair of the speakers `left` and `right`.
+    pub fn stereo_pair(left: &SpeakerInfo, right: &SpeakerInfo) -> Self {
+        let mut channel_map = Self::new();
+        channel_map
+            .push(left.uuid(), &[ChannelRole::LF, ChannelRole::LF])
+            .push(right.uuid(), &[ChannelRole::RF, ChannelRole::RF]);
+        channel_map
+    }
+
+    /// Adds a speaker playing `roles` to the channel map.
+    pub fn push(&mut self, uuid: impl Into<String>, roles: &[ChannelRole]) -> &mut Self {
+        self.entries.push((uuid.into(), roles.to_vec()));
+        self
+    }
+
+    /// The speakers in this channel map as UUID and the channels they play.
+    pub fn entries(&self) -> &[(String, Vec<ChannelRole>)] {
+        &self.entries
+    }
+
+    /// The channels played by the speaker with the UUID `uuid`.
+    pub fn roles(&self, uuid: &str) -> Option<&[ChannelRole]> {
+        self.entries
+            .iter()
+            .find(|(entry, _)| entry.eq_ignore_ascii_case(uuid))
+            .map(|(_, roles)| roles.as_slice())
+    }
+
+    /// Whether this channel map describes a stereo pair.
+    pub fn is_stereo_pair(&self) -> bool {
+        let has = |role| {
+            self.entries
+                .iter()
+                .any(|(_, roles)| !roles.is_empty() && roles.iter().all(|r| *r == role))
+        };
+        self.entries.len() == 2 && has(ChannelRole::LF) && has(ChannelRole::RF)
+    }
+}
+
+impl fmt::Display for ChannelMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (uuid, roles)) in self.entries.iter().enumerate() {
+            if i > 0 {
+                f.write_str(";")?;
+            }
+            write!(f, "{}:", uuid)?;
+            for (j, role) in roles.iter().enumerate() {
+                if j > 0 {
+                    f.write_str(",")?;
+                }
+                write!(f, "{}", role)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for ChannelMap {
+    type Err = ParseChannelMapError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let entries = s
+            .split(';')
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (uuid, roles) = entry.split_once(':').ok_or(ParseChannelMapError)?;
+                if uuid.is_empty() || roles.is_empty() {
+                    return Err(ParseChannelMapError);
+                }
+                let roles = roles.split(',').map(str::parse).collect::<Result<_, _>>()?;
+                Ok((uuid.to_string(), roles))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { entries })
+    }
+}
+
+/// The channel map of a home theater setup, with a primary speaker (usually a soundbar) playing
+/// `LF,RF`, an optional subwoofer playing `SW` and optional rear surrounds playing `LR` and `RR`.
+/// Its string representation is the `HTSatChanMapSet` used by sonos, e.g.
+/// `RINCON_A:LF,RF;RINCON_B:SW;RINCON_C:LR;RINCON_D:RR`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtChannelMap(ChannelMap);
+
+impl HtChannelMap {
+    /// Creates a channel map containing only the primary speaker.
+    pub fn new(primary: &SpeakerInfo) -> Self {
+        let mut channel_map = ChannelMap::new();
+        channel_map.push(primary.uuid(), &[ChannelRole::LF, ChannelRole::RF]);
+        Self(channel_map)
+    }
+
+    fn set(&mut self, uuid: &str, role: ChannelRole) -> &mut Self {
+        self.0.entries.retain(|(_, roles)| roles != &[role]);
+        self.0.push(uuid, &[role]);
+        self
+    }
+
+    fn find(&self, role: ChannelRole) -> Option<&str> {
+        self.0
+            .entries
+            .iter()
+            .find(|(_, roles)| roles.as_slice() == [role])
+            .map(|(uuid, _)| uuid.as_str())
+    }
+
+    /// Sets the subwoofer, replacing the previous one.
+    pub fn set_sub(&mut self, sub: &SpeakerInfo) -> &mut Self {
+        self.set(sub.uuid(), ChannelRole::SW)
+    }
+
+    /// Sets the rear surrounds, replacing the previous ones.
+    pub fn set_rear_surrounds(&mut self, left: &SpeakerInfo, right: &SpeakerInfo) -> &mut Self {
+        self.set(left.uuid(), ChannelRole::LR)
+            .set(right.uuid(), ChannelRole::RR)
+    }
+
+    /// The UUID of the speaker playing the front channels.
+    pub fn primary(&self) -> Option<&str> {
+        self.0
+            .entries
+            .iter()
+            .find(|(_, roles)| roles.contains(&ChannelRole::LF) && roles.contains(&ChannelRole::RF))
+            .map(|(uuid, _)| uuid.as_str())
+    }
+    /// The UUID of the subwoofer.
+    pub fn sub(&self) -> Option<&str> {
+        self.find(ChannelRole::SW)
+    }
+    /// The UUID of the left rear surround.
+    pub fn left_rear(&self) -> Option<&str> {
+        self.find(ChannelRole::LR)
+    }
+    /// The UUID of the right rear surround.
+    pub fn right_rear(&self) -> Option<&str> {
+        self.find(ChannelRole::RR)
+    }
+
+    /// The underlying channel map.
+    pub fn channel_map(&self) -> &ChannelMap {
+        &self.0
+    }
+}
+
+impl fmt::Display for HtChannelMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl std::str::FromStr for HtChannelMap {
+    type Err = ParseChannelMapError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let channel_map = HtChannelMap(s.parse()?);
+        match channel_map.primary() {
+            Some(_) => Ok(channel_map),
+            None => Err(ParseChannelMapError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stereo_pair() {
+        let channel_map: ChannelMap =
+            "RINCON_000E58000000001400:LF,LF;RINCON_000E58000000002400:RF,RF"
+                .parse()
+                .unwrap();
+        assert!(channel_map.is_stereo_pair());
+        assert_eq!(
+            channel_map.roles("rincon_000e58000000002400"),
+            Some(&[ChannelRole::RF, ChannelRole::RF][..])
+        );
+        assert_eq!(
+            channel_map.to_string(),
+            "RINCON_000E58000000001400:LF,LF;RINCON_000E58000000002400:RF,RF"
+        );
+    }
+
+    #[test]
+    fn empty_roles() {
+        for s in &[
+            "RINCON_000E58000000001400:;RINCON_000E58000000002400:RF,RF",
+            "RINCON_000E58000000001400;RINCON_000E58000000002400:RF,RF",
+            "RINCON_000E58000000001400:LF,,LF",
+            ":LF,LF",
+        ] {
+            assert!(s.parse::<ChannelMap>().is_err(), "{} was accepted", s);
+        }
+
+        let mut channel_map = ChannelMap::new();
+        channel_map.push("RINCON_000E58000000001400", &[]).push(
+            "RINCON_000E58000000002400",
+            &[ChannelRole::RF, ChannelRole::RF],
+        );
+        assert!(!channel_map.is_stereo_pair());
+    }
+
+    #[test]
+    fn not_a_stereo_pair() {
+        let channel_map: ChannelMap =
+            "RINCON_000E58000000001400:LF,RF;RINCON_000E58000000002400:SW"
+                .parse()
+                .unwrap();
+        assert!(!channel_map.is_stereo_pair());
+        assert!("".parse::<ChannelMap>().unwrap().entries().is_empty());
+    }
+
+    #[test]
+    fn ht_channel_map_round_trip() {
+        for s in &[
+            "RINCON_48A6B8000000001400:LF,RF;RINCON_5CAAFD000000001400:SW",
+            "RINCON_48A6B8000000001400:LF,RF;RINCON_949F3E000000001400:LR;RINCON_949F3E000000002400:RR",
+            "RINCON_48A6B8000000001400:LF,RF;RINCON_5CAAFD000000001400:SW;RINCON_949F3E000000001400:LR;RINCON_949F3E000000002400:RR",
+        ] {
+            let channel_map: HtChannelMap = s.parse().unwrap();
+            assert_eq!(channel_map.primary(), Some("RINCON_48A6B8000000001400"));
+            assert_eq!(channel_map.to_string(), *s);
+        }
+        assert!("RINCON_5CAAFD000000001400:SW"
+            .parse::<HtChannelMap>()
+            .is_err());
+    }
+
+    #[test]
+    fn ht_channel_map_roles() {
+        let channel_map: HtChannelMap = "RINCON_48A6B8000000001400:LF,RF;RINCON_5CAAFD000000001400:SW;RINCON_949F3E000000001400:LR;RINCON_949F3E000000002400:RR"
+            .parse()
+            .unwrap();
+        assert_eq!(channel_map.sub(), Some("RINCON_5CAAFD000000001400"));
+        assert_eq!(channel_map.left_rear(), Some("RINCON_949F3E000000001400"));
+        assert_eq!(channel_map.right_rear(), Some("RINCON_949F3E000000002400"));
+
+        let channel_map: HtChannelMap = "RINCON_48A6B8000000001400:LF,RF".parse().unwrap();
+        assert_eq!(channel_map.sub(), None);
+        assert_eq!(channel_map.left_rear(), None);
+    }
+}
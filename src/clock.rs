@@ -0,0 +1,338 @@
+use crate::Result;
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A date and time without a time zone, as used by the sonos clock (`YYYY-MM-DD HH:MM:SS`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+#[allow(missing_docs)]
+impl DateTime {
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Interprets the date and time as UTC.
+    pub fn to_system_time(&self) -> SystemTime {
+        // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let (year, month, day) = (
+            i64::from(self.year),
+            i64::from(self.month),
+            i64::from(self.day),
+        );
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        let days = era * 146_097 + day_of_era - 719_468;
+
+        let seconds = days * 86400
+            + i64::from(self.hour) * 3600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second);
+
+        if seconds >= 0 {
+            UNIX_EPOCH + Duration::from_secs(seconds as u64)
+        } else {
+            UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs())
+        }
+    }
+}
+
+impl From<SystemTime> for DateTime {
+    /// Converts the system time to UTC. Times before the unix epoch are clamped to it.
+    fn from(time: SystemTime) -> Self {
+        let seconds = time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let days = (seconds / 86400) as i64;
+        let seconds_of_day = seconds % 86400;
+
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+            hour: (seconds_of_day / 3600) as u8,
+            minute: (seconds_of_day / 60 % 60) as u8,
+            second: (seconds_of_day % 60) as u8,
+        }
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseDateTimeError;
+impl std::error::Error for ParseDateTimeError {}
+impl fmt::Display for ParseDateTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "provided string was not in the format `YYYY-MM-DD HH:MM:SS`".fmt(f)
+    }
+}
+
+impl std::str::FromStr for DateTime {
+    type Err = ParseDateTimeError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let opt = (|| {
+            let (date, time) = s.trim().split_once(' ')?;
+            let mut date = date.splitn(3, '-');
+            let mut time = time.splitn(3, ':');
+
+            let date_time = DateTime {
+                year: date.next()?.parse().ok()?,
+                month: date.next()?.parse().ok()?,
+                day: date.next()?.parse().ok()?,
+                hour: time.next()?.parse().ok()?,
+                minute: time.next()?.parse().ok()?,
+                second: time.next()?.parse().ok()?,
+            };
+            let valid = (1..=12).contains(&date_time.month)
+                && (1..=days_in_month(date_time.year, date_time.month)).contains(&date_time.day)
+                && date_time.hour < 24
+                && date_time.minute < 60
+                && date_time.second < 60;
+
+            Some(date_time).filter(|_| valid)
+        })();
+
+        opt.ok_or(ParseDateTimeError)
+    }
+}
+
+// `is_multiple_of` would need Rust 1.87
+#[allow(unknown_lints, clippy::manual_is_multiple_of)]
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+pub(crate) fn parse_date_time(s: &str) -> Result<DateTime> {
+    s.parse()
+        .map_err(|e| rupnp::Error::invalid_response(e).into())
+}
+
+/// The current time of the household, as returned by
+/// [Speaker::time_now](struct.Speaker.html#method.time_now).
+#[derive(Debug, Clone)]
+pub struct HouseholdTime {
+    pub(crate) utc_time: DateTime,
+    pub(crate) local_time: DateTime,
+    pub(crate) time_zone: String,
+    pub(crate) time_generation: u32,
+}
+
+#[allow(missing_docs)]
+impl HouseholdTime {
+    pub fn utc_time(&self) -> DateTime {
+        self.utc_time
+    }
+    pub fn local_time(&self) -> DateTime {
+        self.local_time
+    }
+    /// The time zone the local time is in, e.g. `+0100`.
+    pub fn time_zone(&self) -> &str {
+        &self.time_zone
+    }
+    /// Gets incremented every time the clock of the household is changed.
+    pub fn time_generation(&self) -> u32 {
+        self.time_generation
+    }
+
+    /// How far the speaker's clock is ahead of `now`, negative when it is behind.
+    /// The resolution is one second.
+    pub fn drift(&self, now: SystemTime) -> i64 {
+        let speaker = self.utc_time.to_system_time();
+        match speaker.duration_since(now) {
+            Ok(ahead) => ahead.as_secs() as i64,
+            Err(behind) => -(behind.duration().as_secs() as i64),
+        }
+    }
+}
+
+/// The time zone configuration of the household.
+#[derive(Debug, Clone)]
+pub struct TimeZone {
+    pub(crate) index: i32,
+    pub(crate) auto_adjust_dst: bool,
+    pub(crate) rule: String,
+}
+
+#[allow(missing_docs)]
+impl TimeZone {
+    /// The index into the list of time zones known to sonos.
+    pub fn index(&self) -> i32 {
+        self.index
+    }
+    pub fn auto_adjust_dst(&self) -> bool {
+        self.auto_adjust_dst
+    }
+    /// The rule describing the UTC offset and daylight saving time of the time zone.
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+}
+
+/// Whether times are displayed in the 12 or 24 hour format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeFormat {
+    /// e.g. `1:30 PM`
+    Hours12,
+    /// e.g. `13:30`
+    Hours24,
+}
+
+impl fmt::Display for TimeFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeFormat::Hours12 => f.write_str("12H"),
+            TimeFormat::Hours24 => f.write_str("24H"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseFormatError;
+impl std::error::Error for ParseFormatError {}
+impl fmt::Display for ParseFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "provided string was not a known time or date format".fmt(f)
+    }
+}
+
+impl std::str::FromStr for TimeFormat {
+    type Err = ParseFormatError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "12H" => Ok(TimeFormat::Hours12),
+            "24H" => Ok(TimeFormat::Hours24),
+            _ => Err(ParseFormatError),
+        }
+    }
+}
+
+/// The order in which day, month and year are displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateFormat {
+    /// e.g. `31.12.2020`
+    DayMonthYear,
+    /// e.g. `12/31/2020`
+    MonthDayYear,
+    /// e.g. `2020-12-31`
+    YearMonthDay,
+}
+
+impl fmt::Display for DateFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateFormat::DayMonthYear => f.write_str("DMY"),
+            DateFormat::MonthDayYear => f.write_str("MDY"),
+            DateFormat::YearMonthDay => f.write_str("YMD"),
+        }
+    }
+}
+
+impl std::str::FromStr for DateFormat {
+    type Err = ParseFormatError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "DMY" => Ok(DateFormat::DayMonthYear),
+            "MDY" => Ok(DateFormat::MonthDayYear),
+            "YMD" => Ok(DateFormat::YearMonthDay),
+            _ => Err(ParseFormatError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_time_round_trip() {
+        for s in &[
+            "2020-12-31 23:59:59",
+            "2024-02-29 00:00:00",
+            "2000-02-29 12:30:05",
+        ] {
+            assert_eq!(s.parse::<DateTime>().unwrap().to_string(), *s);
+        }
+    }
+
+    #[test]
+    fn impossible_dates() {
+        for s in &[
+            "2021-02-31 00:00:00",
+            "2021-02-29 00:00:00",
+            "1900-02-29 00:00:00",
+            "2021-04-31 00:00:00",
+            "2021-13-01 00:00:00",
+            "2021-01-00 00:00:00",
+            "2021-01-01 24:00:00",
+        ] {
+            assert!(s.parse::<DateTime>().is_err(), "{} was accepted", s);
+        }
+    }
+
+    #[test]
+    fn system_time_conversion() {
+        let date_time: DateTime = "2021-03-14 15:09:26".parse().unwrap();
+        assert_eq!(DateTime::from(date_time.to_system_time()), date_time);
+        assert_eq!(
+            DateTime::from(UNIX_EPOCH).to_string(),
+            "1970-01-01 00:00:00"
+        );
+    }
+}
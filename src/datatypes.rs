@@ -4,10 +4,11 @@ use std::{
     cmp::PartialEq,
     fmt,
     hash::{Hash, Hasher},
+    ops::RangeInclusive,
 };
 
 /// This enum describes how Sonos repeats the current playlist.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum RepeatMode {
     /// The playlist doesn't get repeated.
     #[default]
@@ -46,6 +47,171 @@ impl std::str::FromStr for RepeatMode {
     }
 }
 
+/// The audio channels of a speaker. Stereo pairs use `LF` for the left and `RF` for the right speaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// Both channels.
+    Master,
+    /// The left front channel.
+    LF,
+    /// The right front channel.
+    RF,
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// This enum describes how the volume is ramped by
+/// [ramp_to_volume](struct.Speaker.html#method.ramp_to_volume).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RampType {
+    /// `SLEEP_TIMER_RAMP_TYPE`: ramps linearly up or down from the current volume at about
+    /// 1.25 steps per second.
+    SleepTimer,
+    /// `ALARM_RAMP_TYPE`: sets the volume to 0, waits about 30 seconds, then ramps up at about
+    /// 2.5 steps per second.
+    Alarm,
+    /// `AUTOPLAY_RAMP_TYPE`: ramps quickly, at about 50 steps per second.
+    Autoplay,
+}
+
+impl fmt::Display for RampType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RampType::SleepTimer => f.write_str("SLEEP_TIMER_RAMP_TYPE"),
+            RampType::Alarm => f.write_str("ALARM_RAMP_TYPE"),
+            RampType::Autoplay => f.write_str("AUTOPLAY_RAMP_TYPE"),
+        }
+    }
+}
+
+/// The extended equalizer settings of a speaker, mostly only supported by home theater setups.
+/// They are read and changed using [eq](struct.Speaker.html#method.eq) and
+/// [set_eq](struct.Speaker.html#method.set_eq).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EqSetting {
+    /// Reduces loud sounds and raises quiet ones. `0` or `1`.
+    NightMode,
+    /// Speech enhancement. `0` or `1`.
+    DialogLevel,
+    /// Whether the subwoofer is turned on. `0` or `1`.
+    SubEnabled,
+    /// The subwoofer level, from `-15` to `15`.
+    SubGain,
+    /// The crossover frequency of the subwoofer in Hz, from `50` to `110`.
+    SubCrossover,
+    /// Whether the phase of the subwoofer is inverted. `0` or `1`.
+    SubPolarity,
+    /// Whether the surround speakers are turned on. `0` or `1`.
+    SurroundEnabled,
+    /// The level of the surround speakers for TV audio, from `-15` to `15`.
+    SurroundLevel,
+    /// The level of the surround speakers for music, from `-15` to `15`.
+    MusicSurroundLevel,
+    /// How music is played on the surround speakers, `0` for ambient and `1` for full.
+    SurroundMode,
+    /// The lip sync delay of the TV input, from `0` to `5`.
+    AudioDelay,
+}
+
+impl EqSetting {
+    /// The values accepted by the speaker for this setting.
+    pub fn range(self) -> RangeInclusive<i16> {
+        match self {
+            EqSetting::SubGain | EqSetting::SurroundLevel | EqSetting::MusicSurroundLevel => {
+                -15..=15
+            }
+            EqSetting::SubCrossover => 50..=110,
+            EqSetting::AudioDelay => 0..=5,
+            EqSetting::NightMode
+            | EqSetting::DialogLevel
+            | EqSetting::SubEnabled
+            | EqSetting::SubPolarity
+            | EqSetting::SurroundEnabled
+            | EqSetting::SurroundMode => 0..=1,
+        }
+    }
+}
+
+impl fmt::Display for EqSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// What happens when audio is detected on the line-in or TV input of a speaker.
+/// It is read by [autoplay_config](struct.Speaker.html#method.autoplay_config) and applied by
+/// [set_autoplay_config](struct.Speaker.html#method.set_autoplay_config).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AutoplayConfig {
+    pub(crate) include_linked_zones: bool,
+    pub(crate) room_uuid: String,
+    pub(crate) volume: u16,
+    pub(crate) use_volume: bool,
+}
+
+#[allow(missing_docs)]
+impl AutoplayConfig {
+    /// Whether the other speakers in the group play the input as well.
+    pub fn include_linked_zones(&self) -> bool {
+        self.include_linked_zones
+    }
+    /// The room which starts playing the input.
+    pub fn room_uuid(&self) -> &str {
+        &self.room_uuid
+    }
+    pub fn volume(&self) -> u16 {
+        self.volume
+    }
+    /// Whether the [volume](struct.AutoplayConfig.html#method.volume) is applied when autoplay starts.
+    pub fn use_volume(&self) -> bool {
+        self.use_volume
+    }
+
+    pub fn set_include_linked_zones(&mut self, include_linked_zones: bool) -> &mut Self {
+        self.include_linked_zones = include_linked_zones;
+        self
+    }
+    pub fn set_room_uuid(&mut self, room_uuid: impl Into<String>) -> &mut Self {
+        self.room_uuid = room_uuid.into();
+        self
+    }
+    pub fn set_volume(&mut self, volume: u16) -> &mut Self {
+        self.volume = volume;
+        self
+    }
+    pub fn set_use_volume(&mut self, use_volume: bool) -> &mut Self {
+        self.use_volume = use_volume;
+        self
+    }
+}
+
+pub(crate) fn parse_play_mode(play_mode: &str) -> Result<(RepeatMode, bool)> {
+    match play_mode.to_uppercase().as_str() {
+        "NORMAL" => Ok((RepeatMode::None, false)),
+        "REPEAT_ALL" => Ok((RepeatMode::All, false)),
+        "REPEAT_ONE" => Ok((RepeatMode::One, false)),
+        "SHUFFLE_NOREPEAT" => Ok((RepeatMode::None, true)),
+        "SHUFFLE" => Ok((RepeatMode::All, true)),
+        "SHUFFLE_REPEAT_ONE" => Ok((RepeatMode::One, true)),
+        _ => Err(rupnp::Error::invalid_response(ParseRepeatModeError).into()),
+    }
+}
+
+pub(crate) fn play_mode_str(repeat_mode: RepeatMode, shuffle: bool) -> &'static str {
+    match (repeat_mode, shuffle) {
+        (RepeatMode::None, false) => "NORMAL",
+        (RepeatMode::One, false) => "REPEAT_ONE",
+        (RepeatMode::All, false) => "REPEAT_ALL",
+        (RepeatMode::None, true) => "SHUFFLE_NOREPEAT",
+        (RepeatMode::One, true) => "SHUFFLE_REPEAT_ONE",
+        (RepeatMode::All, true) => "SHUFFLE",
+    }
+}
+
 /// A more lightweight representation of a speaker containing only the name, uuid and location.
 /// It gets returned by the [zone_group_state](struct.Speaker.html#method.zone_group_state) function.
 #[derive(Debug, Eq)]
@@ -53,6 +219,8 @@ pub struct SpeakerInfo {
     pub(crate) name: String,
     pub(crate) uuid: String,
     pub(crate) location: String,
+    pub(crate) channel_map_set: Option<String>,
+    pub(crate) ht_sat_chan_map_set: Option<String>,
 }
 impl PartialEq for SpeakerInfo {
     fn eq(&self, other: &Self) -> bool {
@@ -71,12 +239,16 @@ impl SpeakerInfo {
         let mut uuid = None;
         let mut name = None;
         let mut location = None;
+        let mut channel_map_set = None;
+        let mut ht_sat_chan_map_set = None;
 
         for attr in node.attributes() {
             match attr.name().to_lowercase().as_str() {
                 "uuid" => uuid = Some(attr.value()),
                 "location" => location = Some(attr.value()),
                 "zonename" => name = Some(attr.value()),
+                "channelmapset" => channel_map_set = Some(attr.value().to_string()),
+                "htsatchanmapset" => ht_sat_chan_map_set = Some(attr.value().to_string()),
                 _ => (),
             }
         }
@@ -106,6 +278,8 @@ impl SpeakerInfo {
                     )
                 })?
                 .to_string(),
+            channel_map_set,
+            ht_sat_chan_map_set,
         })
     }
 
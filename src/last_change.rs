@@ -0,0 +1,403 @@
+use crate::{
+    datatypes, utils, RepeatMode, Result, Track, TransportActions, TransportSource, TransportState,
+    ValidPlayModes,
+};
+use roxmltree::{Document, Node};
+use std::str::FromStr;
+
+/// Iterates over the `(name, channel, value)` triples of the first `InstanceID` in a
+/// `LastChange` document.
+fn for_each_value<'a>(
+    document: &'a Document<'a>,
+    mut f: impl FnMut(&str, Option<&str>, &'a str) -> Result<()>,
+) -> Result<()> {
+    let instance = utils::find_root_node(document, "InstanceID", "LastChange")?;
+
+    for node in instance.children().filter(Node::is_element) {
+        let value = match node.attribute("val") {
+            Some(value) if !value.eq_ignore_ascii_case("not_implemented") => value,
+            _ => continue,
+        };
+        f(node.tag_name().name(), node.attribute("channel"), value)?;
+    }
+
+    Ok(())
+}
+
+fn parse<T: FromStr>(value: &str) -> Result<T>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    value
+        .parse()
+        .map_err(|e| rupnp::Error::invalid_response(e).into())
+}
+
+/// Metadata which isn't a track, e.g. the title-only items of radio streams, is skipped instead of
+/// failing the whole change. The raw metadata is kept alongside.
+fn parse_metadata(value: &str) -> Option<Track> {
+    if value.is_empty() {
+        return None;
+    }
+    match Track::from_metadata(value) {
+        Ok(track) => Some(track),
+        Err(err) => {
+            log::debug!("skipping track metadata which isn't a track: {}", err);
+            None
+        }
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    Some(value.to_string()).filter(|value| !value.is_empty())
+}
+
+/// The changed state variables of the AVTransport service, as sent in its `LastChange` event.
+/// Every field is `None` when it wasn't part of the change.
+#[derive(Debug, Default)]
+pub struct AvTransportChange {
+    transport_state: Option<TransportState>,
+    repeat_mode: Option<RepeatMode>,
+    shuffle: Option<bool>,
+    crossfade: Option<bool>,
+    number_of_tracks: Option<u32>,
+    current_track_no: Option<u32>,
+    current_track_duration: Option<u32>,
+    current_track_uri: Option<String>,
+    current_track: Option<Track>,
+    current_track_metadata: Option<String>,
+    next_track_uri: Option<String>,
+    next_track: Option<Track>,
+    next_track_metadata: Option<String>,
+    transport_uri: Option<String>,
+    transport_uri_metadata: Option<String>,
+    enqueued_transport_uri: Option<String>,
+    current_transport_actions: Option<TransportActions>,
+    current_valid_play_modes: Option<ValidPlayModes>,
+    sleep_timer_generation: Option<u32>,
+}
+
+#[allow(missing_docs)]
+impl AvTransportChange {
+    /// Parses the (unescaped) value of the `LastChange` state variable.
+    pub fn from_last_change(last_change: &str) -> Result<Self> {
+        let document = Document::parse(last_change)?;
+        let mut change = Self::default();
+
+        for_each_value(&document, |name, _, value| {
+            match name {
+                "TransportState" => change.transport_state = Some(parse(value)?),
+                "CurrentPlayMode" => {
+                    let (repeat_mode, shuffle) = datatypes::parse_play_mode(value)?;
+                    change.repeat_mode = Some(repeat_mode);
+                    change.shuffle = Some(shuffle);
+                }
+                "CurrentCrossfadeMode" => change.crossfade = Some(utils::parse_bool(value)?),
+                "NumberOfTracks" => change.number_of_tracks = Some(parse(value)?),
+                "CurrentTrack" => change.current_track_no = Some(parse(value)?),
+                "CurrentTrackDuration" if !value.is_empty() => {
+                    change.current_track_duration = Some(utils::seconds_from_str(value)?)
+                }
+                "CurrentTrackURI" => change.current_track_uri = non_empty(value),
+                "CurrentTrackMetaData" => {
+                    change.current_track = parse_metadata(value);
+                    change.current_track_metadata = non_empty(value);
+                }
+                "NextTrackURI" => change.next_track_uri = non_empty(value),
+                "NextTrackMetaData" => {
+                    change.next_track = parse_metadata(value);
+                    change.next_track_metadata = non_empty(value);
+                }
+                "AVTransportURI" => change.transport_uri = non_empty(value),
+                "AVTransportURIMetaData" => change.transport_uri_metadata = non_empty(value),
+                "EnqueuedTransportURI" => change.enqueued_transport_uri = non_empty(value),
+                "CurrentTransportActions" => change.current_transport_actions = Some(parse(value)?),
+                "CurrentValidPlayModes" => change.current_valid_play_modes = Some(parse(value)?),
+                "SleepTimerGeneration" => change.sleep_timer_generation = Some(parse(value)?),
+                _ => (),
+            }
+            Ok(())
+        })?;
+
+        Ok(change)
+    }
+
+    pub fn transport_state(&self) -> Option<TransportState> {
+        self.transport_state
+    }
+    pub fn repeat_mode(&self) -> Option<RepeatMode> {
+        self.repeat_mode
+    }
+    pub fn shuffle(&self) -> Option<bool> {
+        self.shuffle
+    }
+    pub fn crossfade(&self) -> Option<bool> {
+        self.crossfade
+    }
+    pub fn number_of_tracks(&self) -> Option<u32> {
+        self.number_of_tracks
+    }
+    /// The first track number is 1.
+    pub fn current_track_no(&self) -> Option<u32> {
+        self.current_track_no
+    }
+    pub fn current_track_duration(&self) -> Option<u32> {
+        self.current_track_duration
+    }
+    pub fn current_track_uri(&self) -> Option<&str> {
+        self.current_track_uri.as_deref()
+    }
+    /// `None` when the metadata doesn't describe a track, e.g. for radio streams.
+    pub fn current_track(&self) -> Option<&Track> {
+        self.current_track.as_ref()
+    }
+    pub fn current_track_metadata(&self) -> Option<&str> {
+        self.current_track_metadata.as_deref()
+    }
+    pub fn next_track_uri(&self) -> Option<&str> {
+        self.next_track_uri.as_deref()
+    }
+    pub fn next_track(&self) -> Option<&Track> {
+        self.next_track.as_ref()
+    }
+    pub fn next_track_metadata(&self) -> Option<&str> {
+        self.next_track_metadata.as_deref()
+    }
+    pub fn transport_uri(&self) -> Option<&str> {
+        self.transport_uri.as_deref()
+    }
+    pub fn transport_uri_metadata(&self) -> Option<&str> {
+        self.transport_uri_metadata.as_deref()
+    }
+    pub fn transport_source(&self) -> Option<TransportSource> {
+        self.transport_uri
+            .as_deref()
+            .and_then(|uri| uri.parse().ok())
+    }
+    pub fn enqueued_transport_uri(&self) -> Option<&str> {
+        self.enqueued_transport_uri.as_deref()
+    }
+    pub fn current_transport_actions(&self) -> Option<TransportActions> {
+        self.current_transport_actions
+    }
+    pub fn current_valid_play_modes(&self) -> Option<ValidPlayModes> {
+        self.current_valid_play_modes
+    }
+    pub fn sleep_timer_generation(&self) -> Option<u32> {
+        self.sleep_timer_generation
+    }
+}
+
+/// The changed state variables of the RenderingControl service, as sent in its `LastChange` event.
+/// Every field is `None` when it wasn't part of the change.
+#[derive(Debug, Default)]
+pub struct RenderingControlChange {
+    volume: Option<u16>,
+    left_volume: Option<u16>,
+    right_volume: Option<u16>,
+    mute: Option<bool>,
+    bass: Option<i8>,
+    treble: Option<i8>,
+    loudness: Option<bool>,
+    night_mode: Option<bool>,
+    dialog_level: Option<bool>,
+    sub_enabled: Option<bool>,
+    sub_gain: Option<i16>,
+    surround_enabled: Option<bool>,
+    surround_level: Option<i16>,
+    music_surround_level: Option<i16>,
+    output_fixed: Option<bool>,
+    headphone_connected: Option<bool>,
+}
+
+#[allow(missing_docs)]
+impl RenderingControlChange {
+    /// Parses the (unescaped) value of the `LastChange` state variable.
+    pub fn from_last_change(last_change: &str) -> Result<Self> {
+        let document = Document::parse(last_change)?;
+        let mut change = Self::default();
+
+        for_each_value(&document, |name, channel, value| {
+            match (name, channel.unwrap_or("Master")) {
+                ("Volume", "Master") => change.volume = Some(parse(value)?),
+                ("Volume", "LF") => change.left_volume = Some(parse(value)?),
+                ("Volume", "RF") => change.right_volume = Some(parse(value)?),
+                ("Mute", "Master") => change.mute = Some(utils::parse_bool(value)?),
+                ("Loudness", "Master") => change.loudness = Some(utils::parse_bool(value)?),
+                ("Bass", _) => change.bass = Some(parse(value)?),
+                ("Treble", _) => change.treble = Some(parse(value)?),
+                ("NightMode", _) => change.night_mode = Some(utils::parse_bool(value)?),
+                ("DialogLevel", _) => change.dialog_level = Some(utils::parse_bool(value)?),
+                ("SubEnabled", _) => change.sub_enabled = Some(utils::parse_bool(value)?),
+                ("SubGain", _) => change.sub_gain = Some(parse(value)?),
+                ("SurroundEnabled", _) => change.surround_enabled = Some(utils::parse_bool(value)?),
+                ("SurroundLevel", _) => change.surround_level = Some(parse(value)?),
+                ("MusicSurroundLevel", _) => change.music_surround_level = Some(parse(value)?),
+                ("OutputFixed", _) => change.output_fixed = Some(utils::parse_bool(value)?),
+                ("HeadphoneConnected", _) => {
+                    change.headphone_connected = Some(utils::parse_bool(value)?)
+                }
+                _ => (),
+            }
+            Ok(())
+        })?;
+
+        Ok(change)
+    }
+
+    /// The volume of the `Master` channel.
+    pub fn volume(&self) -> Option<u16> {
+        self.volume
+    }
+    /// The volume of the left (`LF`) channel.
+    pub fn left_volume(&self) -> Option<u16> {
+        self.left_volume
+    }
+    /// The volume of the right (`RF`) channel.
+    pub fn right_volume(&self) -> Option<u16> {
+        self.right_volume
+    }
+    pub fn mute(&self) -> Option<bool> {
+        self.mute
+    }
+    pub fn bass(&self) -> Option<i8> {
+        self.bass
+    }
+    pub fn treble(&self) -> Option<i8> {
+        self.treble
+    }
+    pub fn loudness(&self) -> Option<bool> {
+        self.loudness
+    }
+    pub fn night_mode(&self) -> Option<bool> {
+        self.night_mode
+    }
+    /// Whether speech enhancement is turned on.
+    pub fn dialog_level(&self) -> Option<bool> {
+        self.dialog_level
+    }
+    pub fn sub_enabled(&self) -> Option<bool> {
+        self.sub_enabled
+    }
+    pub fn sub_gain(&self) -> Option<i16> {
+        self.sub_gain
+    }
+    pub fn surround_enabled(&self) -> Option<bool> {
+        self.surround_enabled
+    }
+    pub fn surround_level(&self) -> Option<i16> {
+        self.surround_level
+    }
+    pub fn music_surround_level(&self) -> Option<i16> {
+        self.music_surround_level
+    }
+    pub fn output_fixed(&self) -> Option<bool> {
+        self.output_fixed
+    }
+    pub fn headphone_connected(&self) -> Option<bool> {
+        self.headphone_connected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUEUE_TRACK: &str = r#"<Event xmlns="urn:schemas-upnp-org:metadata-1-0/AVT/" xmlns:r="urn:schemas-rinconnetworks-com:metadata-1-0/"><InstanceID val="0"><TransportState val="PLAYING"/><CurrentPlayMode val="SHUFFLE_NOREPEAT"/><CurrentCrossfadeMode val="0"/><NumberOfTracks val="12"/><CurrentTrack val="3"/><CurrentSection val="0"/><CurrentTrackURI val="x-file-cifs://nas/music/Daft%20Punk/Discovery/03%20Digital%20Love.flac"/><CurrentTrackDuration val="0:04:58"/><CurrentTrackMetaData val="&lt;DIDL-Lite xmlns:dc=&quot;http://purl.org/dc/elements/1.1/&quot; xmlns:upnp=&quot;urn:schemas-upnp-org:metadata-1-0/upnp/&quot; xmlns:r=&quot;urn:schemas-rinconnetworks-com:metadata-1-0/&quot; xmlns=&quot;urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/&quot;&gt;&lt;item id=&quot;-1&quot; parentID=&quot;-1&quot; restricted=&quot;true&quot;&gt;&lt;res protocolInfo=&quot;x-file-cifs:*:audio/flac:*&quot; duration=&quot;0:04:58&quot;&gt;x-file-cifs://nas/music/Daft%20Punk/Discovery/03%20Digital%20Love.flac&lt;/res&gt;&lt;upnp:albumArtURI&gt;/getaa?u=x-file-cifs%3a%2f%2fnas&amp;amp;v=1&lt;/upnp:albumArtURI&gt;&lt;upnp:class&gt;object.item.audioItem.musicTrack&lt;/upnp:class&gt;&lt;dc:title&gt;Digital Love&lt;/dc:title&gt;&lt;dc:creator&gt;Daft Punk&lt;/dc:creator&gt;&lt;upnp:album&gt;Discovery&lt;/upnp:album&gt;&lt;/item&gt;&lt;/DIDL-Lite&gt;"/><r:NextTrackURI val="x-file-cifs://nas/music/Daft%20Punk/Discovery/04%20Harder.flac"/><r:NextTrackMetaData val=""/><r:EnqueuedTransportURI val="x-rincon-playlist:RINCON_000E58000000001400#A:ALBUM/Discovery"/><r:EnqueuedTransportURIMetaData val=""/><PlaybackStorageMedium val="NETWORK"/><AVTransportURI val="x-rincon-queue:RINCON_000E58000000001400#0"/><AVTransportURIMetaData val=""/><NextAVTransportURI val=""/><NextAVTransportURIMetaData val=""/><CurrentTransportActions val="Set, Stop, Pause, Play, X_DLNA_SeekTime, Next, X_DLNA_SeekTrackNr, Previous"/><r:CurrentValidPlayModes val="SHUFFLE,REPEAT,REPEATONE,CROSSFADE"/><r:MuseSessions val=""/><r:DirectControlClientID val=""/><r:DirectControlIsSuspended val="0"/><r:DirectControlAccountID val=""/><TransportStatus val="OK"/><r:SleepTimerGeneration val="2"/><r:AlarmRunning val="0"/><r:SnoozeRunning val="0"/><r:RestartPending val="0"/><TransportPlaySpeed val="NOT_IMPLEMENTED"/><CurrentMediaDuration val="NOT_IMPLEMENTED"/><RecordStorageMedium val="NOT_IMPLEMENTED"/><PossiblePlaybackStorageMedia val="NONE, NETWORK"/><PossibleRecordStorageMedia val="NOT_IMPLEMENTED"/><RecordMediumWriteStatus val="NOT_IMPLEMENTED"/><CurrentRecordQualityMode val="NOT_IMPLEMENTED"/><PossibleRecordQualityModes val="NOT_IMPLEMENTED"/></InstanceID></Event>"#;
+
+    const RADIO: &str = r#"<Event xmlns="urn:schemas-upnp-org:metadata-1-0/AVT/" xmlns:r="urn:schemas-rinconnetworks-com:metadata-1-0/"><InstanceID val="0"><TransportState val="TRANSITIONING"/><CurrentPlayMode val="NORMAL"/><CurrentCrossfadeMode val="0"/><NumberOfTracks val="1"/><CurrentTrack val="1"/><CurrentSection val="0"/><CurrentTrackURI val="x-sonosapi-stream:s24940?sid=254&amp;flags=8224&amp;sn=0"/><CurrentTrackDuration val=""/><CurrentTrackMetaData val="&lt;DIDL-Lite xmlns:dc=&quot;http://purl.org/dc/elements/1.1/&quot; xmlns:upnp=&quot;urn:schemas-upnp-org:metadata-1-0/upnp/&quot; xmlns:r=&quot;urn:schemas-rinconnetworks-com:metadata-1-0/&quot; xmlns=&quot;urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/&quot;&gt;&lt;item id=&quot;-1&quot; parentID=&quot;-1&quot; restricted=&quot;true&quot;&gt;&lt;r:streamContent&gt;&lt;/r:streamContent&gt;&lt;dc:title&gt;BBC Radio 6 Music&lt;/dc:title&gt;&lt;upnp:class&gt;object.item&lt;/upnp:class&gt;&lt;/item&gt;&lt;/DIDL-Lite&gt;"/><r:NextTrackURI val=""/><r:NextTrackMetaData val=""/><AVTransportURI val="x-sonosapi-stream:s24940?sid=254&amp;flags=8224&amp;sn=0"/><AVTransportURIMetaData val="&lt;DIDL-Lite xmlns:dc=&quot;http://purl.org/dc/elements/1.1/&quot; xmlns:upnp=&quot;urn:schemas-upnp-org:metadata-1-0/upnp/&quot; xmlns:r=&quot;urn:schemas-rinconnetworks-com:metadata-1-0/&quot; xmlns=&quot;urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/&quot;&gt;&lt;item id=&quot;-1&quot; parentID=&quot;-1&quot; restricted=&quot;true&quot;&gt;&lt;dc:title&gt;BBC Radio 6 Music&lt;/dc:title&gt;&lt;upnp:class&gt;object.item.audioItem.audioBroadcast&lt;/upnp:class&gt;&lt;desc id=&quot;cdudn&quot; nameSpace=&quot;urn:schemas-rinconnetworks-com:metadata-1-0/&quot;&gt;SA_RINCON65031_&lt;/desc&gt;&lt;/item&gt;&lt;/DIDL-Lite&gt;"/><CurrentTransportActions val="Set, Stop, Pause, Play"/><r:CurrentValidPlayModes val=""/><TransportStatus val="OK"/><r:SleepTimerGeneration val="0"/><CurrentMediaDuration val="NOT_IMPLEMENTED"/></InstanceID></Event>"#;
+
+    const RENDERING_CONTROL: &str = r#"<Event xmlns="urn:schemas-upnp-org:metadata-1-0/RCS/"><InstanceID val="0"><Volume channel="Master" val="17"/><Volume channel="LF" val="100"/><Volume channel="RF" val="100"/><Mute channel="Master" val="0"/><Mute channel="LF" val="0"/><Mute channel="RF" val="0"/><Bass val="-2"/><Treble val="3"/><Loudness channel="Master" val="1"/><OutputFixed val="0"/><HeadphoneConnected val="0"/><SpeakerSize val="5"/><SubGain val="0"/><SubCrossover val="0"/><SubPolarity val="0"/><SubEnabled val="1"/><SonarEnabled val="0"/><SonarCalibrationAvailable val="0"/><PresetNameList val="FactoryDefaults"/></InstanceID></Event>"#;
+
+    #[test]
+    fn av_transport_queue_track() {
+        let change = AvTransportChange::from_last_change(QUEUE_TRACK).unwrap();
+
+        assert_eq!(change.transport_state(), Some(TransportState::Playing));
+        assert_eq!(change.repeat_mode(), Some(RepeatMode::None));
+        assert_eq!(change.shuffle(), Some(true));
+        assert_eq!(change.crossfade(), Some(false));
+        assert_eq!(change.number_of_tracks(), Some(12));
+        assert_eq!(change.current_track_no(), Some(3));
+        assert_eq!(change.current_track_duration(), Some(298));
+
+        let track = change.current_track().unwrap();
+        assert_eq!(track.title(), "Digital Love");
+        assert_eq!(track.creator(), Some("Daft Punk"));
+        assert_eq!(track.album(), Some("Discovery"));
+        assert_eq!(track.duration(), Some(298));
+
+        assert!(change.next_track().is_none());
+        assert!(change.next_track_metadata().is_none());
+        assert_eq!(
+            change.transport_source(),
+            Some(TransportSource::Queue(
+                "RINCON_000E58000000001400#0".to_string()
+            ))
+        );
+        let actions = change.current_transport_actions().unwrap();
+        assert!(actions.contains(TransportActions::NEXT | TransportActions::PREVIOUS));
+        let play_modes = change.current_valid_play_modes().unwrap();
+        assert!(play_modes.shuffle() && play_modes.repeat_one());
+        assert_eq!(change.sleep_timer_generation(), Some(2));
+    }
+
+    #[test]
+    fn av_transport_radio_without_res() {
+        let change = AvTransportChange::from_last_change(RADIO).unwrap();
+
+        assert_eq!(
+            change.transport_state(),
+            Some(TransportState::Transitioning)
+        );
+        assert_eq!(change.repeat_mode(), Some(RepeatMode::None));
+        assert_eq!(change.current_track_duration(), None);
+        assert!(change.current_track().is_none());
+        assert!(change
+            .current_track_metadata()
+            .unwrap()
+            .contains("BBC Radio 6 Music"));
+        assert!(change
+            .transport_uri_metadata()
+            .unwrap()
+            .contains("audioBroadcast"));
+        assert!(matches!(
+            change.transport_source(),
+            Some(TransportSource::Radio(_))
+        ));
+        let actions = change.current_transport_actions().unwrap();
+        assert!(actions.contains(TransportActions::PLAY));
+        assert!(!actions.contains(TransportActions::NEXT));
+        assert_eq!(
+            change.current_valid_play_modes(),
+            Some(ValidPlayModes::default())
+        );
+    }
+
+    #[test]
+    fn rendering_control() {
+        let change = RenderingControlChange::from_last_change(RENDERING_CONTROL).unwrap();
+
+        assert_eq!(change.volume(), Some(17));
+        assert_eq!(change.left_volume(), Some(100));
+        assert_eq!(change.right_volume(), Some(100));
+        assert_eq!(change.mute(), Some(false));
+        assert_eq!(change.bass(), Some(-2));
+        assert_eq!(change.treble(), Some(3));
+        assert_eq!(change.loudness(), Some(true));
+        assert_eq!(change.output_fixed(), Some(false));
+        assert_eq!(change.sub_enabled(), Some(true));
+        assert_eq!(change.sub_gain(), Some(0));
+        assert_eq!(change.night_mode(), None);
+    }
+
+    #[test]
+    fn partial_change() {
+        let last_change = r#"<Event xmlns="urn:schemas-upnp-org:metadata-1-0/RCS/"><InstanceID val="0"><Volume channel="Master" val="42"/></InstanceID></Event>"#;
+        let change = RenderingControlChange::from_last_change(last_change).unwrap();
+
+        assert_eq!(change.volume(), Some(42));
+        assert_eq!(change.mute(), None);
+        assert_eq!(change.left_volume(), None);
+    }
+}
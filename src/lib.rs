@@ -13,6 +13,7 @@
 //! ```rust,no_run
 //! # use futures::prelude::*;
 //! # use std::time::Duration;
+//! use sonor::CurrentTrack;
 //! # async fn f() -> Result<(), sonor::Error> {
 //! let speaker = sonor::find("your room name", Duration::from_secs(2)).await?
 //!     .expect("room exists");
@@ -20,7 +21,8 @@
 //! println!("The volume is currently at {}", speaker.volume().await?);
 //!
 //! match speaker.track().await? {
-//!     Some(track_info) => println!("- Currently playing '{}", track_info.track()),
+//!     Some(CurrentTrack::Track(track_info)) => println!("- Currently playing '{}", track_info.track()),
+//!     Some(CurrentTrack::Tv) => println!("- Currently playing the TV"),
 //!     None => println!("- No track currently playing"),
 //! }
 //!
@@ -43,30 +45,52 @@
 //! # async fn f() -> Result<(), sonor::Error> {
 //! # let speaker = sonor::find("your room name", Duration::from_secs(2)).await?.expect("room exists");
 //!
-//! let service = URN::service("schemas-upnp-org", "GroupRenderingControl", 1);
-//! let args = "<InstanceID>0</InstanceID>";
-//! let response = speaker.action(&service, "GetGroupMute", args).await?;
+//! let service = URN::service("schemas-upnp-org", "DeviceProperties", 1);
+//! let response = speaker.action(&service, "GetHouseholdID", "").await?;
 //!
-//! println!("{}", response["CurrentMute"]);
+//! println!("{}", response["CurrentHouseholdID"]);
 //!
 //! # Ok(())
 //! # };
 //! ```
 
+mod alarm;
+mod channel_map;
+mod clock;
 mod datatypes;
 mod discovery;
+mod last_change;
+mod line_in;
+mod playlist;
+mod queue;
 mod snapshot;
 mod speaker;
+mod subscription;
 mod track;
+mod transport;
 mod utils;
+mod zone_info;
 
-pub use datatypes::{RepeatMode, SpeakerInfo};
+pub use alarm::{Alarm, Days, Recurrence};
+pub use channel_map::{ChannelMap, ChannelRole, HtChannelMap};
+pub use clock::{DateFormat, DateTime, HouseholdTime, TimeFormat, TimeZone};
+pub use datatypes::{AutoplayConfig, Channel, EqSetting, RampType, RepeatMode, SpeakerInfo};
 pub use discovery::{discover, find};
+pub use last_change::{AvTransportChange, RenderingControlChange};
+pub use line_in::{LineIn, LineInChange};
+pub use playlist::{PlaylistTracks, SonosPlaylist};
+pub use queue::{EnqueueResult, QueuePage};
 pub use rupnp::{self, ssdp::URN};
 pub use snapshot::Snapshot;
 pub use speaker::Speaker;
+pub use subscription::{Event, Service, Subscription};
 use thiserror::*;
-pub use track::{Track, TrackInfo};
+pub use track::{CurrentTrack, Track, TrackInfo};
+pub use transport::{
+    DeviceCapabilities, MediaInfo, TransportActions, TransportInfo, TransportSource,
+    TransportState, ValidPlayModes,
+};
+pub use zone_info::{Hardware, ZoneInfo};
 
 /// Represents an error encountered by Sonor
 #[derive(Error, Debug)]
@@ -91,6 +115,64 @@ pub enum Error {
         /// The action payload
         payload: String,
     },
+    /// This error is produced when attempting to subscribe to a service
+    /// which is not present on the speaker.
+    #[error("Service {service} was not found when subscribing to events")]
+    MissingServiceForSubscription {
+        /// The service that was subscribed to
+        service: URN,
+    },
+    /// This error is produced when a value is rejected before sending it to the speaker
+    /// because it is outside of the range the speaker accepts.
+    #[error("{value} is outside of the valid range {min}..={max} for {name}")]
+    ValueOutOfRange {
+        /// The name of the setting
+        name: String,
+        /// The rejected value
+        value: i32,
+        /// The smallest accepted value
+        min: i32,
+        /// The largest accepted value
+        max: i32,
+    },
+    /// The queue was changed while reading it in chunks with
+    /// [queue_stream](struct.Speaker.html#method.queue_stream), so tracks could be missing or
+    /// repeated.
+    #[error("the queue was changed while it was being read")]
+    QueueChanged,
+    /// A range of tracks passed to a queue edit like
+    /// [remove_tracks](struct.Speaker.html#method.remove_tracks) is empty or inverted.
+    #[error("the range {start}..{end} doesn't contain any tracks")]
+    EmptyRange {
+        /// The start of the rejected range
+        start: u32,
+        /// The end of the rejected range
+        end: u32,
+    },
+    /// The volume of the speaker can't be changed because its output is fixed,
+    /// see [output_fixed](struct.Speaker.html#method.output_fixed).
+    #[error("the volume can't be changed because the speaker's output is fixed")]
+    VolumeFixed,
+    /// The speaker has no TV input, see [supports_tv](struct.Speaker.html#method.supports_tv).
+    #[error("the speaker doesn't have a TV input")]
+    TvNotSupported,
+    /// The action isn't allowed for what the speaker is currently playing,
+    /// e.g. `Next` while playing a radio stream.
+    #[error("{action} is not allowed for the current transport source {transport_source:?}")]
+    ActionNotAllowed {
+        /// The rejected action
+        action: String,
+        /// What the speaker was playing from, if it could be determined
+        transport_source: Option<TransportSource>,
+    },
+    /// The alarm can't be updated because it has no ID, see
+    /// [Speaker::update_alarm](struct.Speaker.html#method.update_alarm).
+    #[error("the alarm has no ID because it wasn't returned by the speaker")]
+    AlarmWithoutId,
+    /// The speaker didn't send the initial event of a subscription in time,
+    /// or the event didn't contain the requested state.
+    #[error("the speaker didn't send the initial event of the {0:?} subscription")]
+    MissingInitialEvent(Service),
     /// An impossible? situation where a speaker isn't included
     /// in its own zone group state
     #[error("asked for zone group state but the speaker doesn't seem to be included there")]
@@ -0,0 +1,136 @@
+use crate::{utils, Result};
+use std::collections::HashMap;
+
+/// The state of the line-in of a speaker, as returned by
+/// [Speaker::line_in](struct.Speaker.html#method.line_in).
+#[derive(Debug, Clone)]
+pub struct LineIn {
+    pub(crate) name: String,
+    pub(crate) icon: String,
+    pub(crate) left_level: i32,
+    pub(crate) right_level: i32,
+    pub(crate) connected: bool,
+}
+
+#[allow(missing_docs)]
+impl LineIn {
+    /// The name of the input as shown in the app.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn icon(&self) -> &str {
+        &self.icon
+    }
+    pub fn left_level(&self) -> i32 {
+        self.left_level
+    }
+    pub fn right_level(&self) -> i32 {
+        self.right_level
+    }
+    /// Whether a cable is plugged into the line-in.
+    pub fn connected(&self) -> bool {
+        self.connected
+    }
+}
+
+/// The changed state of the line-in, as sent in `AudioIn` events.
+/// The initial event contains every value, later ones only those which changed.
+#[derive(Debug, Clone, Default)]
+pub struct LineInChange {
+    name: Option<String>,
+    icon: Option<String>,
+    left_level: Option<i32>,
+    right_level: Option<i32>,
+    connected: Option<bool>,
+}
+
+#[allow(missing_docs)]
+impl LineInChange {
+    pub(crate) fn from_properties(mut map: HashMap<String, String>) -> Result<Self> {
+        let level = |value: Option<String>| -> Result<Option<i32>> {
+            value
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|e| rupnp::Error::invalid_response(e).into())
+                })
+                .transpose()
+        };
+
+        Ok(Self {
+            name: map.remove("AudioInputName"),
+            icon: map.remove("Icon"),
+            left_level: level(map.remove("LeftLineInLevel"))?,
+            right_level: level(map.remove("RightLineInLevel"))?,
+            connected: map
+                .remove("LineInConnected")
+                .map(utils::parse_bool)
+                .transpose()?,
+        })
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    pub fn icon(&self) -> Option<&str> {
+        self.icon.as_deref()
+    }
+    pub fn left_level(&self) -> Option<i32> {
+        self.left_level
+    }
+    pub fn right_level(&self) -> Option<i32> {
+        self.right_level
+    }
+    /// Whether a cable is plugged into the line-in.
+    pub fn connected(&self) -> Option<bool> {
+        self.connected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn properties(properties: &[(&str, &str)]) -> HashMap<String, String> {
+        properties
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn initial_event() {
+        let change = LineInChange::from_properties(properties(&[
+            ("AudioInputName", "Record Player"),
+            ("Icon", "x-rincon-roomicon:turntable"),
+            ("LineInConnected", "1"),
+            ("LeftLineInLevel", "1"),
+            ("RightLineInLevel", "1"),
+            ("Playing", "0"),
+        ]))
+        .unwrap();
+
+        assert_eq!(change.name(), Some("Record Player"));
+        assert_eq!(change.icon(), Some("x-rincon-roomicon:turntable"));
+        assert_eq!(change.left_level(), Some(1));
+        assert_eq!(change.right_level(), Some(1));
+        assert_eq!(change.connected(), Some(true));
+    }
+
+    #[test]
+    fn unplugged() {
+        let change =
+            LineInChange::from_properties(properties(&[("LineInConnected", "0")])).unwrap();
+
+        assert_eq!(change.connected(), Some(false));
+        assert_eq!(change.name(), None);
+        assert_eq!(change.left_level(), None);
+        assert_eq!(change.right_level(), None);
+    }
+
+    #[test]
+    fn invalid_level() {
+        let properties = properties(&[("LeftLineInLevel", "loud")]);
+        assert!(LineInChange::from_properties(properties).is_err());
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{utils, Result, Track};
+use roxmltree::Node;
+
+/// A sonos playlist, which is a saved queue stored on the speakers.
+/// Playlists are listed with [Speaker::playlists](struct.Speaker.html#method.playlists) and
+/// created with [Speaker::create_playlist](struct.Speaker.html#method.create_playlist) or
+/// [Speaker::save_queue](struct.Speaker.html#method.save_queue).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SonosPlaylist {
+    id: String,
+    title: String,
+    uri: String,
+}
+
+#[allow(missing_docs)]
+impl SonosPlaylist {
+    pub(crate) fn from_xml(node: Node<'_, '_>) -> Result<Self> {
+        let child = |name| {
+            node.children()
+                .find(|c| c.tag_name().name().eq_ignore_ascii_case(name))
+                .and_then(|c| c.text())
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        Ok(Self {
+            id: utils::find_node_attribute(node, "id")?.to_string(),
+            title: child("title"),
+            uri: child("res"),
+        })
+    }
+
+    /// The object ID in the content directory, e.g. `SQ:3`.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+    /// e.g. `file:///jffs/settings/savedqueues.rsq#3`
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// The DIDL-Lite metadata of the playlist, needed to enqueue it or to use it as the
+    /// [program of an alarm](struct.Alarm.html#method.set_program).
+    pub fn metadata(&self) -> String {
+        format!(
+            concat!(
+                r#"<DIDL-Lite xmlns:dc="http://purl.org/dc/elements/1.1/" "#,
+                r#"xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/" "#,
+                r#"xmlns:r="urn:schemas-rinconnetworks-com:metadata-1-0/" "#,
+                r#"xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/">"#,
+                r#"<container id="{}" parentID="SQ:" restricted="true">"#,
+                r#"<dc:title>{}</dc:title>"#,
+                r#"<upnp:class>object.container.playlistContainer</upnp:class>"#,
+                r#"<desc id="cdudn" nameSpace="urn:schemas-rinconnetworks-com:metadata-1-0/">"#,
+                r#"RINCON_AssociatedZPUDN</desc></container></DIDL-Lite>"#
+            ),
+            utils::escape(&self.id),
+            utils::escape(&self.title)
+        )
+    }
+}
+
+/// The tracks of a sonos playlist, as returned by
+/// [Speaker::playlist_tracks](struct.Speaker.html#method.playlist_tracks).
+#[derive(Debug)]
+pub struct PlaylistTracks {
+    pub(crate) tracks: Vec<Track>,
+    pub(crate) update_id: u32,
+}
+
+#[allow(missing_docs)]
+impl PlaylistTracks {
+    pub fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+    pub fn into_tracks(self) -> Vec<Track> {
+        self.tracks
+    }
+    /// Gets incremented every time the playlist is changed. The methods editing a playlist, like
+    /// [add_to_playlist](struct.Speaker.html#method.add_to_playlist), take it to detect
+    /// concurrent changes.
+    pub fn update_id(&self) -> u32 {
+        self.update_id
+    }
+}
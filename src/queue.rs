@@ -0,0 +1,98 @@
+use crate::{utils::HashMapExt, Result, Track};
+use roxmltree::Document;
+use std::collections::HashMap;
+
+/// A part of the queue, as returned by
+/// [Speaker::queue_page](struct.Speaker.html#method.queue_page).
+#[derive(Debug)]
+pub struct QueuePage {
+    tracks: Vec<Track>,
+    number_returned: u32,
+    total_matches: u32,
+    update_id: u32,
+}
+
+#[allow(missing_docs)]
+impl QueuePage {
+    pub(crate) fn from_response(mut map: HashMap<String, String>) -> Result<Self> {
+        let parse = |value: String| -> Result<u32> {
+            value
+                .parse()
+                .map_err(|e| rupnp::Error::invalid_response(e).into())
+        };
+
+        let result = map.extract("Result")?;
+        let tracks = Document::parse(&result)?
+            .root()
+            .first_element_child()
+            .ok_or_else(|| rupnp::Error::ParseError("Queue Response contains no children"))?
+            .children()
+            .filter(roxmltree::Node::is_element)
+            .map(Track::from_xml)
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            tracks,
+            number_returned: parse(map.extract("NumberReturned")?)?,
+            total_matches: parse(map.extract("TotalMatches")?)?,
+            update_id: parse(map.extract("UpdateID")?)?,
+        })
+    }
+
+    pub fn tracks(&self) -> &[Track] {
+        &self.tracks
+    }
+    pub fn into_tracks(self) -> Vec<Track> {
+        self.tracks
+    }
+    pub fn number_returned(&self) -> u32 {
+        self.number_returned
+    }
+    /// The length of the whole queue.
+    pub fn total_matches(&self) -> u32 {
+        self.total_matches
+    }
+    /// Gets incremented every time the queue is changed.
+    pub fn update_id(&self) -> u32 {
+        self.update_id
+    }
+}
+
+/// Where and how many tracks were added to the queue, as returned by
+/// [Speaker::queue_end](struct.Speaker.html#method.queue_end) and the other enqueueing methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnqueueResult {
+    first_track_number: u32,
+    num_tracks_added: u32,
+    new_queue_length: u32,
+}
+
+#[allow(missing_docs)]
+impl EnqueueResult {
+    pub(crate) fn from_response(mut map: HashMap<String, String>) -> Result<Self> {
+        let mut parse = |key: &str| -> Result<u32> {
+            map.extract(key)?
+                .parse()
+                .map_err(|e| rupnp::Error::invalid_response(e).into())
+        };
+
+        Ok(Self {
+            first_track_number: parse("FirstTrackNumberEnqueued")?,
+            num_tracks_added: parse("NumTracksAdded")?,
+            new_queue_length: parse("NewQueueLength")?,
+        })
+    }
+
+    /// The number of the first added track, which can be passed to
+    /// [seek_track](struct.Speaker.html#method.seek_track).
+    pub fn first_track_number(&self) -> u32 {
+        self.first_track_number
+    }
+    /// More than one for containers like albums or playlists.
+    pub fn num_tracks_added(&self) -> u32 {
+        self.num_tracks_added
+    }
+    pub fn new_queue_length(&self) -> u32 {
+        self.new_queue_length
+    }
+}
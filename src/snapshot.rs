@@ -1,4 +1,7 @@
-use crate::{track::TrackInfo, Result, Speaker};
+use crate::{
+    track::{CurrentTrack, TrackInfo},
+    Result, Speaker, TransportSource,
+};
 use futures_util::future::{try_join, try_join4};
 
 /// A Snapshot of the state the speaker is in right now.
@@ -10,7 +13,7 @@ pub struct Snapshot {
     is_playing: Option<bool>,
     track_info: Option<TrackInfo>,
 
-    transport_uri: Option<String>,
+    transport_source: Option<TransportSource>,
 }
 
 impl Snapshot {
@@ -32,26 +35,38 @@ impl Snapshot {
         self
     }
 
-    /// Specifies the current track info
+    /// Specifies the current transport URI, see
+    /// [set_transport_source](struct.Snapshot.html#method.set_transport_source)
     pub fn set_transport_uri(&mut self, transport_uri: impl Into<String>) -> &mut Self {
-        self.transport_uri = Some(transport_uri.into());
+        self.transport_source = transport_uri.into().parse().ok();
+        self
+    }
+
+    /// Specifies the current transport source
+    pub fn set_transport_source(&mut self, transport_source: TransportSource) -> &mut Self {
+        self.transport_source = Some(transport_source);
         self
     }
 
     pub(crate) async fn from_speaker(speaker: &Speaker) -> Result<Self> {
-        let (volume, track_info, is_playing, transport_uri) = try_join4(
+        let (volume, track_info, is_playing, transport_source) = try_join4(
             speaker.volume(),
             speaker.track(),
             speaker.is_playing(),
-            speaker.transport_uri(),
+            speaker.transport_source(),
         )
         .await?;
 
+        let track_info = match track_info {
+            Some(CurrentTrack::Track(track_info)) => Some(track_info),
+            Some(CurrentTrack::Tv) | None => None,
+        };
+
         Ok(Self {
             volume: Some(volume),
             track_info,
             is_playing: Some(is_playing),
-            transport_uri,
+            transport_source,
         })
     }
 
@@ -60,11 +75,11 @@ impl Snapshot {
             speaker.set_volume(volume).await?;
         }
 
-        match &self.transport_uri {
-            Some(uri) if uri.starts_with("x-sonos-vli") => {
+        match &self.transport_source {
+            Some(TransportSource::Vli(_)) => {
                 log::warn!("unsupported transport uri: 'x-sonos-vli:...'")
             }
-            Some(uri) => speaker.set_transport_uri(uri, "").await?,
+            Some(source) => speaker.set_transport_source(source).await?,
             None => {}
         }
 
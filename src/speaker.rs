@@ -1,23 +1,56 @@
 use crate::{
+    alarm::Alarm,
     args,
-    track::{Track, TrackInfo},
+    channel_map::{ChannelMap, HtChannelMap},
+    clock::{self, DateFormat, DateTime, HouseholdTime, TimeFormat, TimeZone},
+    datatypes::{self, AutoplayConfig, Channel},
+    line_in::LineIn,
+    playlist::{PlaylistTracks, SonosPlaylist},
+    queue::{EnqueueResult, QueuePage},
+    track::{CurrentTrack, Track, TrackInfo},
+    transport::{
+        DeviceCapabilities, MediaInfo, TransportActions, TransportInfo, TransportSource,
+        TransportState, ValidPlayModes,
+    },
     utils::{self, HashMapExt},
-    Error, RepeatMode, Result, Snapshot, SpeakerInfo,
+    zone_info::{Hardware, ZoneInfo},
+    EqSetting, Error, Event, RampType, RepeatMode, Result, Service, Snapshot, SpeakerInfo,
+    Subscription,
+};
+use futures_util::{
+    future::{try_join3, try_join4},
+    stream::{self, Stream},
+    TryStreamExt,
 };
 use roxmltree::{Document, Node};
 use rupnp::{ssdp::URN, Device};
-use std::{collections::HashMap, net::Ipv4Addr};
+use std::{
+    collections::HashMap,
+    net::Ipv4Addr,
+    ops::{Range, RangeInclusive},
+    time::Duration,
+};
 
 pub(crate) const SONOS_URN: URN = URN::device("schemas-upnp-org", "ZonePlayer", 1);
 
-const AV_TRANSPORT: &URN = &URN::service("schemas-upnp-org", "AVTransport", 1);
+pub(crate) const AV_TRANSPORT: &URN = &URN::service("schemas-upnp-org", "AVTransport", 1);
 const DEVICE_PROPERTIES: &URN = &URN::service("schemas-upnp-org", "DeviceProperties", 1);
-const RENDERING_CONTROL: &URN = &URN::service("schemas-upnp-org", "RenderingControl", 1);
-const ZONE_GROUP_TOPOLOGY: &URN = &URN::service("schemas-upnp-org", "ZoneGroupTopology", 1);
+pub(crate) const RENDERING_CONTROL: &URN = &URN::service("schemas-upnp-org", "RenderingControl", 1);
+pub(crate) const ZONE_GROUP_TOPOLOGY: &URN =
+    &URN::service("schemas-upnp-org", "ZoneGroupTopology", 1);
+const GROUP_RENDERING_CONTROL: &URN = &URN::service("schemas-upnp-org", "GroupRenderingControl", 1);
 const QUEUE: &URN = &URN::service("schemas-sonos-com", "Queue", 1);
+const HT_CONTROL: &URN = &URN::service("schemas-upnp-org", "HTControl", 1);
+const ALARM_CLOCK: &URN = &URN::service("schemas-upnp-org", "AlarmClock", 1);
+pub(crate) const AUDIO_IN: &URN = &URN::service("schemas-upnp-org", "AudioIn", 1);
+const CONTENT_DIRECTORY: &URN = &URN::service("schemas-upnp-org", "ContentDirectory", 1);
 const MUSIC_SERVICES: &URN = &URN::service("schemas-upnp-org", "MusicServices", 1);
 
 const DEFAULT_ARGS: &str = "<InstanceID>0</InstanceID>";
+/// How many items are requested at once when browsing the queue or the content directory.
+const PAGE_SIZE: u32 = 100;
+/// How many tracks can be enqueued at once with `AddMultipleURIsToQueue`.
+const MAX_URIS_PER_ENQUEUE: usize = 16;
 
 #[derive(Debug, Clone)]
 /// A sonos speaker, wrapping a UPnP-Device and providing user-oriented methods in an asynyronous
@@ -56,17 +89,257 @@ impl Speaker {
             .await?
             .extract("CurrentZoneName")
     }
+    /// Renames the room, keeping its icon and configuration.
+    pub async fn set_name(&self, name: &str) -> Result<()> {
+        let mut map = self
+            .action(DEVICE_PROPERTIES, "GetZoneAttributes", "")
+            .await?;
+        let icon = map.remove("CurrentIcon").unwrap_or_default();
+        let configuration = map.remove("CurrentConfiguration").unwrap_or_default();
+
+        self.set_zone_attributes(name, &icon, &configuration).await
+    }
+    /// The icon of the room, e.g. `x-rincon-roomicon:living`.
+    pub async fn icon(&self) -> Result<String> {
+        Ok(self
+            .action(DEVICE_PROPERTIES, "GetZoneAttributes", "")
+            .await?
+            .remove("CurrentIcon")
+            .unwrap_or_default())
+    }
+    pub async fn set_zone_attributes(
+        &self,
+        name: &str,
+        icon: &str,
+        configuration: &str,
+    ) -> Result<()> {
+        let args = args! {
+            "DesiredZoneName": utils::escape(name),
+            "DesiredIcon": utils::escape(icon),
+            "DesiredConfiguration": utils::escape(configuration)
+        };
+        self.action(DEVICE_PROPERTIES, "SetZoneAttributes", args)
+            .await
+            .map(drop)
+    }
+
+    pub async fn zone_info(&self) -> Result<ZoneInfo> {
+        let map = self.action(DEVICE_PROPERTIES, "GetZoneInfo", "").await?;
+        ZoneInfo::from_response(map)
+    }
+    /// Returns the model of the speaker together with its [zone_info](struct.Speaker.html#method.zone_info).
+    pub async fn hardware(&self) -> Result<Hardware> {
+        Ok(Hardware {
+            model_name: self.0.model_name().to_string(),
+            model_number: self.0.model_number().map(str::to_string),
+            zone_info: self.zone_info().await?,
+        })
+    }
+
+    /// Reads the autoplay settings for `source`, which is empty for the speaker's default input.
+    pub async fn autoplay_config(&self, source: &str) -> Result<AutoplayConfig> {
+        let args = args! { "Source": source };
+        let (mut include_linked_zones, mut room_uuid, mut volume, mut use_volume) = try_join4(
+            self.action(DEVICE_PROPERTIES, "GetAutoplayLinkedZones", args),
+            self.action(DEVICE_PROPERTIES, "GetAutoplayRoomUUID", args),
+            self.action(DEVICE_PROPERTIES, "GetAutoplayVolume", args),
+            self.action(DEVICE_PROPERTIES, "GetUseAutoplayVolume", args),
+        )
+        .await?;
+
+        Ok(AutoplayConfig {
+            include_linked_zones: include_linked_zones
+                .extract("IncludeLinkedZones")
+                .and_then(utils::parse_bool)?,
+            room_uuid: room_uuid.remove("RoomUUID").unwrap_or_default(),
+            volume: volume
+                .extract("CurrentVolume")?
+                .parse()
+                .map_err(rupnp::Error::invalid_response)?,
+            use_volume: use_volume
+                .extract("UseVolume")
+                .and_then(utils::parse_bool)?,
+        })
+    }
+    /// Applies the autoplay settings for `source`, only sending the ones which differ from the
+    /// current settings.
+    pub async fn set_autoplay_config(&self, source: &str, config: &AutoplayConfig) -> Result<()> {
+        let current = self.autoplay_config(source).await?;
+
+        if current.include_linked_zones != config.include_linked_zones {
+            let args =
+                args! { "IncludeLinkedZones": config.include_linked_zones as u8, "Source": source };
+            self.action(DEVICE_PROPERTIES, "SetAutoplayLinkedZones", args)
+                .await?;
+        }
+        if current.room_uuid != config.room_uuid {
+            let args = args! { "RoomUUID": config.room_uuid, "Source": source };
+            self.action(DEVICE_PROPERTIES, "SetAutoplayRoomUUID", args)
+                .await?;
+        }
+        if current.volume != config.volume {
+            let args = args! { "Volume": config.volume, "Source": source };
+            self.action(DEVICE_PROPERTIES, "SetAutoplayVolume", args)
+                .await?;
+        }
+        if current.use_volume != config.use_volume {
+            let args = args! { "UseVolume": config.use_volume as u8, "Source": source };
+            self.action(DEVICE_PROPERTIES, "SetUseAutoplayVolume", args)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether the white status light is turned on.
+    pub async fn led_state(&self) -> Result<bool> {
+        self.action(DEVICE_PROPERTIES, "GetLEDState", "")
+            .await?
+            .extract("CurrentLEDState")
+            .map(|x| x.eq_ignore_ascii_case("on"))
+    }
+    pub async fn set_led_state(&self, on: bool) -> Result<()> {
+        let args = args! { "DesiredLEDState": if on { "On" } else { "Off" } };
+        self.action(DEVICE_PROPERTIES, "SetLEDState", args)
+            .await
+            .map(drop)
+    }
+    /// Whether the buttons on the speaker are locked.
+    pub async fn button_lock(&self) -> Result<bool> {
+        self.action(DEVICE_PROPERTIES, "GetButtonLockState", "")
+            .await?
+            .extract("CurrentButtonLockState")
+            .map(|x| x.eq_ignore_ascii_case("on"))
+    }
+    pub async fn set_button_lock(&self, locked: bool) -> Result<()> {
+        let args = args! { "DesiredButtonLockState": if locked { "On" } else { "Off" } };
+        self.action(DEVICE_PROPERTIES, "SetButtonLockState", args)
+            .await
+            .map(drop)
+    }
 
     pub async fn uuid(&self) -> Result<String> {
-        let uuid = self
-            ._zone_group_state()
+        self.speaker_info()
+            .await
+            .map(|speaker_info| speaker_info.uuid)
+    }
+
+    /// Returns the entry of this speaker in the zone group state.
+    async fn speaker_info(&self) -> Result<SpeakerInfo> {
+        self._zone_group_state()
             .await?
             .into_iter()
             .flat_map(|(_, speakers)| speakers)
             .find(|speaker_info| self.0.url() == speaker_info.location())
-            .map(|speaker_info| speaker_info.uuid);
+            .ok_or(Error::SpeakerNotIncludedInOwnZoneGroupState)
+    }
+
+    /// Returns the channel map of the bonded setup (e.g. a stereo pair) this speaker is part of,
+    /// or `None` when the speaker isn't bonded.
+    pub async fn channel_map(&self) -> Result<Option<ChannelMap>> {
+        self.speaker_info()
+            .await?
+            .channel_map_set
+            .filter(|channel_map_set| !channel_map_set.is_empty())
+            .map(|channel_map_set| {
+                channel_map_set
+                    .parse()
+                    .map_err(|e| rupnp::Error::invalid_response(e).into())
+            })
+            .transpose()
+    }
 
-        uuid.ok_or(Error::SpeakerNotIncludedInOwnZoneGroupState)
+    /// Creates a stereo pair with `left` playing the left and `right` playing the right channel.
+    /// The pair shows up as one room with the name of `left`.
+    ///
+    /// `CreateStereoPair` is only accepted by the speaker becoming the left one, so the action is
+    /// sent to `left` even if this speaker is `right` or not part of the pair at all.
+    pub async fn create_stereo_pair(&self, left: &SpeakerInfo, right: &SpeakerInfo) -> Result<()> {
+        let args = args! { "ChannelMapSet": ChannelMap::stereo_pair(left, right) };
+        self.speaker_at(left.location())
+            .await?
+            .action(DEVICE_PROPERTIES, "CreateStereoPair", args)
+            .await
+            .map(drop)
+    }
+
+    /// Separates the stereo pair this speaker is part of.
+    /// Does nothing when the speaker isn't part of a stereo pair.
+    pub async fn separate_stereo_pair(&self) -> Result<()> {
+        let channel_map = match self.channel_map().await? {
+            Some(channel_map) if channel_map.is_stereo_pair() => channel_map,
+            _ => return Ok(()),
+        };
+
+        let args = args! { "ChannelMapSet": channel_map };
+        self.action(DEVICE_PROPERTIES, "SeparateStereoPair", args)
+            .await
+            .map(drop)
+    }
+
+    /// Returns the channel map of the home theater setup this speaker is part of,
+    /// or `None` when it isn't part of one.
+    pub async fn ht_channel_map(&self) -> Result<Option<HtChannelMap>> {
+        self.speaker_info()
+            .await?
+            .ht_sat_chan_map_set
+            .filter(|ht_sat_chan_map_set| !ht_sat_chan_map_set.is_empty())
+            .map(|ht_sat_chan_map_set| {
+                ht_sat_chan_map_set
+                    .parse()
+                    .map_err(|e| rupnp::Error::invalid_response(e).into())
+            })
+            .transpose()
+    }
+
+    /// Bonds a subwoofer to this speaker, which has to be a home theater primary like a soundbar.
+    pub async fn add_subwoofer(&self, sub: &SpeakerInfo) -> Result<()> {
+        let mut channel_map = HtChannelMap::new(&self.speaker_info().await?);
+        channel_map.set_sub(sub);
+
+        let args = args! { "ChannelMapSet": channel_map };
+        self.action(DEVICE_PROPERTIES, "AddBondedZones", args)
+            .await
+            .map(drop)
+    }
+    /// Removes a subwoofer previously bonded with [add_subwoofer](struct.Speaker.html#method.add_subwoofer).
+    pub async fn remove_subwoofer(&self, sub: &SpeakerInfo) -> Result<()> {
+        let mut channel_map = HtChannelMap::new(&self.speaker_info().await?);
+        channel_map.set_sub(sub);
+
+        let args = args! { "ChannelMapSet": channel_map, "KeepGrouped": 0 };
+        self.action(DEVICE_PROPERTIES, "RemoveBondedZones", args)
+            .await
+            .map(drop)
+    }
+
+    /// Adds rear surrounds to this speaker, which has to be a home theater primary like a soundbar.
+    /// A bonded subwoofer stays part of the setup, previous rear surrounds are replaced.
+    pub async fn add_rear_surrounds(&self, left: &SpeakerInfo, right: &SpeakerInfo) -> Result<()> {
+        let mut channel_map = match self.ht_channel_map().await? {
+            Some(channel_map) => channel_map,
+            None => HtChannelMap::new(&self.speaker_info().await?),
+        };
+        channel_map.set_rear_surrounds(left, right);
+
+        let args = args! { "HTSatChanMapSet": channel_map };
+        self.action(DEVICE_PROPERTIES, "AddHTSatellite", args)
+            .await
+            .map(drop)
+    }
+    /// Removes rear surrounds previously added with
+    /// [add_rear_surrounds](struct.Speaker.html#method.add_rear_surrounds).
+    pub async fn remove_rear_surrounds(
+        &self,
+        left: &SpeakerInfo,
+        right: &SpeakerInfo,
+    ) -> Result<()> {
+        for satellite in &[left, right] {
+            let args = args! { "SatRoomUUID": satellite.uuid() };
+            self.action(DEVICE_PROPERTIES, "RemoveHTSatellite", args)
+                .await?;
+        }
+        Ok(())
     }
 
     // AV_TRANSPORT
@@ -89,48 +362,72 @@ impl Speaker {
             Err(err) => Err(err),
         }
     }
+    /// Performs a transport action, turning the UPnP error 701 (transition not available)
+    /// into `Error::ActionNotAllowed`.
+    async fn transport_action(&self, action: &str, payload: &str) -> Result<()> {
+        match self.action(AV_TRANSPORT, action, payload).await {
+            Err(Error::UPnP(rupnp::Error::UPnPError(err))) if err.err_code() == 701 => {
+                Err(Error::ActionNotAllowed {
+                    action: action.to_string(),
+                    transport_source: self.transport_source().await.ok().flatten(),
+                })
+            }
+            res => res.map(drop),
+        }
+    }
+
+    /// Returns `Error::ActionNotAllowed` when not playing from the queue.
     pub async fn next(&self) -> Result<()> {
-        self.action(AV_TRANSPORT, "Next", DEFAULT_ARGS)
-            .await
-            .map(drop)
+        self.transport_action("Next", DEFAULT_ARGS).await
     }
+    /// Returns `Error::ActionNotAllowed` when not playing from the queue.
     pub async fn previous(&self) -> Result<()> {
-        self.action(AV_TRANSPORT, "Previous", DEFAULT_ARGS)
-            .await
-            .map(drop)
+        self.transport_action("Previous", DEFAULT_ARGS).await
     }
 
+    /// Returns `Error::ActionNotAllowed` when the current source can't be seeked, e.g. radio.
     pub async fn skip_to(&self, seconds: u32) -> Result<()> {
         let args = args! { "InstanceID": 0, "Unit": "REL_TIME", "Target": utils::seconds_to_str(seconds.into())};
-        self.action(AV_TRANSPORT, "Seek", args).await.map(drop)
+        self.transport_action("Seek", args).await
     }
     pub async fn skip_by(&self, seconds: i32) -> Result<()> {
         let args = args! { "InstanceID": 0, "Unit": "TIME_DELTA", "Target": utils::seconds_to_str(seconds.into())};
-        self.action(AV_TRANSPORT, "Seek", args).await.map(drop)
+        self.transport_action("Seek", args).await
     }
     /// The first track number is 1.
     pub async fn seek_track(&self, track_no: u32) -> Result<()> {
         let args = args! { "InstanceID": 0, "Unit": "TRACK_NR", "Target": track_no };
-        self.action(AV_TRANSPORT, "Seek", args).await.map(drop)
+        self.transport_action("Seek", args).await
     }
 
-    async fn playback_mode(&self) -> Result<(RepeatMode, bool)> {
-        let play_mode = self
-            .action(AV_TRANSPORT, "GetTransportSettings", DEFAULT_ARGS)
-            .await?
-            .extract("PlayMode")?;
-
-        match play_mode.to_uppercase().as_str() {
-            "NORMAL" => Ok((RepeatMode::None, false)),
-            "REPEAT_ALL" => Ok((RepeatMode::All, false)),
-            "REPEAT_ONE" => Ok((RepeatMode::One, false)),
-            "SHUFFLE_NOREPEAT" => Ok((RepeatMode::None, true)),
-            "SHUFFLE" => Ok((RepeatMode::All, true)),
-            "SHUFFLE_REPEAT_ONE" => Ok((RepeatMode::One, true)),
-            _ => Err(Error::UPnP(rupnp::Error::invalid_response(
-                crate::datatypes::ParseRepeatModeError,
-            ))),
+    /// The actions which are currently allowed, depending on what the speaker is playing.
+    pub async fn current_transport_actions(&self) -> Result<TransportActions> {
+        self.action(AV_TRANSPORT, "GetCurrentTransportActions", DEFAULT_ARGS)
+            .await?
+            .extract("Actions")?
+            .parse()
+            .map_err(|e| rupnp::Error::invalid_response(e).into())
+    }
+    /// The play modes supported by what the speaker is currently playing.
+    ///
+    /// There is no action for this, so every call opens a short-lived
+    /// [subscription](struct.Speaker.html#method.subscribe) to the `AVTransport` service and waits
+    /// up to 5 seconds for its initial event. To follow the play modes, subscribe once and read
+    /// [AvTransportChange::current_valid_play_modes](struct.AvTransportChange.html#method.current_valid_play_modes)
+    /// instead.
+    pub async fn valid_play_modes(&self) -> Result<ValidPlayModes> {
+        match self.initial_event(Service::AVTransport).await? {
+            Event::AVTransport(change) => change.current_valid_play_modes(),
+            _ => None,
         }
+        .ok_or(Error::MissingInitialEvent(Service::AVTransport))
+    }
+
+    async fn playback_mode(&self) -> Result<(RepeatMode, bool)> {
+        self.action(AV_TRANSPORT, "GetTransportSettings", DEFAULT_ARGS)
+            .await?
+            .extract("PlayMode")
+            .and_then(|play_mode| datatypes::parse_play_mode(&play_mode))
     }
     pub async fn repeat_mode(&self) -> Result<RepeatMode> {
         self.playback_mode()
@@ -142,14 +439,7 @@ impl Speaker {
     }
 
     async fn set_playback_mode(&self, repeat_mode: RepeatMode, shuffle: bool) -> Result<()> {
-        let playback_mode = match (repeat_mode, shuffle) {
-            (RepeatMode::None, false) => "NORMAL",
-            (RepeatMode::One, false) => "REPEAT_ONE",
-            (RepeatMode::All, false) => "REPEAT_ALL",
-            (RepeatMode::None, true) => "SHUFFLE_NOREPEAT",
-            (RepeatMode::One, true) => "SHUFFLE_REPEAT_ONE",
-            (RepeatMode::All, true) => "SHUFFLE",
-        };
+        let playback_mode = datatypes::play_mode_str(repeat_mode, shuffle);
         self.action(
             AV_TRANSPORT,
             "SetPlayMode",
@@ -180,18 +470,90 @@ impl Speaker {
             .map(drop)
     }
 
+    async fn remaining_sleep_timer(&self) -> Result<(Option<Duration>, u32)> {
+        let mut map = self
+            .action(AV_TRANSPORT, "GetRemainingSleepTimerDuration", DEFAULT_ARGS)
+            .await?;
+
+        let remaining = map
+            .remove("RemainingSleepTimerDuration")
+            .filter(|remaining| !remaining.is_empty())
+            .map(|remaining| utils::seconds_from_str(&remaining))
+            .transpose()?
+            .map(|seconds| Duration::from_secs(seconds.into()));
+        let generation = map
+            .extract("CurrentSleepTimerGeneration")?
+            .parse()
+            .map_err(rupnp::Error::invalid_response)?;
+
+        Ok((remaining, generation))
+    }
+    /// Returns the time until playback stops, or `None` when no sleep timer is set.
+    pub async fn sleep_timer(&self) -> Result<Option<Duration>> {
+        self.remaining_sleep_timer()
+            .await
+            .map(|(remaining, _)| remaining)
+    }
+    /// Gets incremented every time the sleep timer is changed.
+    pub async fn sleep_timer_generation(&self) -> Result<u32> {
+        self.remaining_sleep_timer()
+            .await
+            .map(|(_, generation)| generation)
+    }
+    /// Stops playback after `duration`, `None` cancels the sleep timer.
+    /// The duration is rounded down to seconds.
+    pub async fn set_sleep_timer(&self, duration: Option<Duration>) -> Result<()> {
+        let duration = duration
+            .map(|duration| utils::seconds_to_str(duration.as_secs() as i64))
+            .unwrap_or_default();
+        let args = args! { "InstanceID": 0, "NewSleepTimerDuration": duration };
+        self.action(AV_TRANSPORT, "ConfigureSleepTimer", args)
+            .await
+            .map(drop)
+    }
+
+    /// Starts playing `program_uri` like it is done when audio is detected on the line-in.
+    pub async fn start_autoplay(
+        &self,
+        program_uri: &str,
+        program_metadata: &str,
+        volume: u16,
+        include_linked_zones: bool,
+        reset_volume_after: bool,
+    ) -> Result<()> {
+        let args = args! {
+            "InstanceID": 0,
+            "ProgramURI": utils::escape(program_uri),
+            "ProgramMetaData": utils::escape(program_metadata),
+            "Volume": volume,
+            "IncludeLinkedZones": include_linked_zones as u8,
+            "ResetVolumeAfter": reset_volume_after as u8
+        };
+        self.action(AV_TRANSPORT, "StartAutoplay", args)
+            .await
+            .map(drop)
+    }
+
     pub async fn is_playing(&self) -> Result<bool> {
-        self.action(AV_TRANSPORT, "GetTransportInfo", DEFAULT_ARGS)
-            .await?
-            .extract("CurrentTransportState")
-            .map(|x| x.eq_ignore_ascii_case("playing"))
+        let transport_info = self.transport_info().await?;
+        Ok(transport_info.state() == TransportState::Playing)
+    }
+    pub async fn transport_info(&self) -> Result<TransportInfo> {
+        let map = self
+            .action(AV_TRANSPORT, "GetTransportInfo", DEFAULT_ARGS)
+            .await?;
+        TransportInfo::from_response(map)
     }
 
-    pub async fn track(&self) -> Result<Option<TrackInfo>> {
+    pub async fn track(&self) -> Result<Option<CurrentTrack>> {
         let mut map = self
             .action(AV_TRANSPORT, "GetPositionInfo", DEFAULT_ARGS)
             .await?;
 
+        if let Some(TransportSource::Tv(_)) = map.get("TrackURI").and_then(|uri| uri.parse().ok()) {
+            return Ok(Some(CurrentTrack::Tv));
+        }
+
         let track_no: u32 = map.extract("Track")?.parse().unwrap();
         let duration = map
             .extract("TrackDuration")
@@ -214,19 +576,59 @@ impl Speaker {
         let duration = utils::seconds_from_str(&duration)?;
         let elapsed = utils::seconds_from_str(&elapsed)?;
 
-        let doc = Document::parse(&metadata)?;
-        let item = utils::find_root_node(&doc, "item", "Track Metadata")?;
-        let track = Track::from_xml(item)?;
+        let track = Track::from_metadata(&metadata)?;
 
-        Ok(Some(TrackInfo::new(
+        Ok(Some(CurrentTrack::Track(TrackInfo::new(
             track, metadata, track_no, duration, elapsed,
-        )))
+        ))))
+    }
+
+    /// Whether the speaker has a TV input, which is the case for home theater models like the
+    /// Playbar, Beam or Arc.
+    pub fn supports_tv(&self) -> bool {
+        self.0.find_service(HT_CONTROL).is_some()
+    }
+    /// Switches the speaker to its TV input.
+    /// Returns `Error::TvNotSupported` for speakers without one.
+    pub async fn switch_to_tv(&self) -> Result<()> {
+        if !self.supports_tv() {
+            return Err(Error::TvNotSupported);
+        }
+        let source = TransportSource::Tv(format!("{}:spdif", self.uuid().await?));
+        self.set_transport_source(&source).await
+    }
+    pub async fn is_playing_tv(&self) -> Result<bool> {
+        let source = self.transport_source().await?;
+        Ok(matches!(source, Some(TransportSource::Tv(_))))
     }
 
     // RENDERING_CONTROL
 
+    /// Performs a volume changing action, turning the failure of a speaker with fixed output
+    /// into `Error::VolumeFixed`.
+    async fn volume_action(&self, action: &str, payload: &str) -> Result<HashMap<String, String>> {
+        match self.action(RENDERING_CONTROL, action, payload).await {
+            Err(Error::UPnP(err)) => match self.output_fixed().await {
+                Ok(true) => Err(Error::VolumeFixed),
+                _ => Err(Error::UPnP(err)),
+            },
+            res => res,
+        }
+    }
+
     pub async fn volume(&self) -> Result<u16> {
-        let args = args! { "InstanceID": 0, "Channel": "Master" };
+        self.channel_volume(Channel::Master).await
+    }
+    pub async fn set_volume(&self, volume: u16) -> Result<()> {
+        self.set_channel_volume(Channel::Master, volume).await
+    }
+    pub async fn set_volume_relative(&self, adjustment: i16) -> Result<u16> {
+        self.set_channel_volume_relative(Channel::Master, adjustment)
+            .await
+    }
+
+    pub async fn channel_volume(&self, channel: Channel) -> Result<u16> {
+        let args = args! { "InstanceID": 0, "Channel": channel };
         self.action(RENDERING_CONTROL, "GetVolume", args)
             .await?
             .extract("CurrentVolume")
@@ -235,32 +637,170 @@ impl Speaker {
                     .map_err(|e| rupnp::Error::invalid_response(e).into())
             })
     }
-    pub async fn set_volume(&self, volume: u16) -> Result<()> {
-        let args = args! { "InstanceID": 0, "Channel": "Master", "DesiredVolume": volume };
-        self.action(RENDERING_CONTROL, "SetVolume", args)
+    pub async fn set_channel_volume(&self, channel: Channel, volume: u16) -> Result<()> {
+        let args = args! { "InstanceID": 0, "Channel": channel, "DesiredVolume": volume };
+        self.volume_action("SetVolume", args).await.map(drop)
+    }
+    pub async fn set_channel_volume_relative(
+        &self,
+        channel: Channel,
+        adjustment: i16,
+    ) -> Result<u16> {
+        let args = args! { "InstanceID": 0, "Channel": channel, "Adjustment": adjustment };
+        self.volume_action("SetRelativeVolume", args)
+            .await?
+            .extract("NewVolume")
+            .and_then(|x| {
+                x.parse()
+                    .map_err(|e| rupnp::Error::invalid_response(e).into())
+            })
+    }
+
+    /// The balance between the left and the right channel, from `-100` (only left) to `100`
+    /// (only right).
+    pub async fn balance(&self) -> Result<i16> {
+        let (left, right) = futures_util::future::try_join(
+            self.channel_volume(Channel::LF),
+            self.channel_volume(Channel::RF),
+        )
+        .await?;
+        Ok(right as i16 - left as i16)
+    }
+    /// Sets the balance between the left and the right channel, from `-100` (only left) to `100`
+    /// (only right).
+    pub async fn set_balance(&self, balance: i16) -> Result<()> {
+        if !(-100..=100).contains(&balance) {
+            return Err(Error::ValueOutOfRange {
+                name: "Balance".to_string(),
+                value: balance.into(),
+                min: -100,
+                max: 100,
+            });
+        }
+
+        let left = 100 - balance.max(0) as u16;
+        let right = 100 - balance.min(0).unsigned_abs();
+        futures_util::future::try_join(
+            self.set_channel_volume(Channel::LF, left),
+            self.set_channel_volume(Channel::RF, right),
+        )
+        .await
+        .map(drop)
+    }
+
+    /// The volume in 1/256 dB.
+    pub async fn volume_db(&self, channel: Channel) -> Result<i16> {
+        let args = args! { "InstanceID": 0, "Channel": channel };
+        self.action(RENDERING_CONTROL, "GetVolumeDB", args)
+            .await?
+            .extract("CurrentVolume")
+            .and_then(|x| {
+                x.parse()
+                    .map_err(|e| rupnp::Error::invalid_response(e).into())
+            })
+    }
+    /// Sets the volume in 1/256 dB, see [volume_db_range](struct.Speaker.html#method.volume_db_range)
+    /// for the accepted values.
+    pub async fn set_volume_db(&self, channel: Channel, volume: i16) -> Result<()> {
+        let args = args! { "InstanceID": 0, "Channel": channel, "DesiredVolume": volume };
+        self.volume_action("SetVolumeDB", args).await.map(drop)
+    }
+    /// The minimum and maximum volume in 1/256 dB.
+    pub async fn volume_db_range(&self, channel: Channel) -> Result<RangeInclusive<i16>> {
+        let args = args! { "InstanceID": 0, "Channel": channel };
+        let mut map = self
+            .action(RENDERING_CONTROL, "GetVolumeDBRange", args)
+            .await?;
+
+        let min = map
+            .extract("MinValue")?
+            .parse()
+            .map_err(rupnp::Error::invalid_response)?;
+        let max = map
+            .extract("MaxValue")?
+            .parse()
+            .map_err(rupnp::Error::invalid_response)?;
+        Ok(min..=max)
+    }
+
+    /// Whether the speaker has a line out whose volume can be fixed, like the Connect or Port.
+    pub async fn supports_output_fixed(&self) -> Result<bool> {
+        self.action(RENDERING_CONTROL, "GetSupportsOutputFixed", DEFAULT_ARGS)
+            .await?
+            .extract("CurrentSupportsFixed")
+            .and_then(utils::parse_bool)
+    }
+    /// When the output is fixed, the volume is controlled by an external amplifier and can't be
+    /// changed on the speaker.
+    pub async fn output_fixed(&self) -> Result<bool> {
+        self.action(RENDERING_CONTROL, "GetOutputFixed", DEFAULT_ARGS)
+            .await?
+            .extract("CurrentFixed")
+            .and_then(utils::parse_bool)
+    }
+    pub async fn set_output_fixed(&self, fixed: bool) -> Result<()> {
+        let args = args! { "InstanceID": 0, "DesiredFixed": fixed as u8 };
+        self.action(RENDERING_CONTROL, "SetOutputFixed", args)
             .await
             .map(drop)
     }
-    pub async fn set_volume_relative(&self, adjustment: i16) -> Result<u16> {
-        let args = args! { "InstanceID": 0, "Channel": "Master", "Adjustment": adjustment };
-        self.action(RENDERING_CONTROL, "SetRelativeVolume", args)
+    pub async fn headphone_connected(&self) -> Result<bool> {
+        self.action(RENDERING_CONTROL, "GetHeadphoneConnected", DEFAULT_ARGS)
             .await?
-            .extract("NewVolume")
+            .extract("CurrentHeadphoneConnected")
+            .and_then(utils::parse_bool)
+    }
+
+    /// Ramps the volume to `volume` and returns how long the ramp takes in seconds.
+    /// With `reset_volume_after` the volume is restored after the playback of `program_uri`
+    /// ends.
+    pub async fn ramp_to_volume(
+        &self,
+        ramp_type: RampType,
+        volume: u16,
+        reset_volume_after: bool,
+        program_uri: &str,
+    ) -> Result<u32> {
+        let args = args! {
+            "InstanceID": 0,
+            "Channel": "Master",
+            "RampType": ramp_type,
+            "DesiredVolume": volume,
+            "ResetVolumeAfter": reset_volume_after as u8,
+            "ProgramURI": utils::escape(program_uri)
+        };
+        self.action(RENDERING_CONTROL, "RampToVolume", args)
+            .await?
+            .extract("RampTime")
             .and_then(|x| {
                 x.parse()
                     .map_err(|e| rupnp::Error::invalid_response(e).into())
             })
     }
+    /// Restores the volume the speaker had before the last
+    /// [ramp_to_volume](struct.Speaker.html#method.ramp_to_volume).
+    pub async fn restore_volume_prior_to_ramp(&self) -> Result<()> {
+        let args = args! { "InstanceID": 0, "Channel": "Master" };
+        self.action(RENDERING_CONTROL, "RestoreVolumePriorToRamp", args)
+            .await
+            .map(drop)
+    }
 
     pub async fn mute(&self) -> Result<bool> {
-        let args = args! { "InstanceID": 0, "Channel": "Master" };
+        self.channel_mute(Channel::Master).await
+    }
+    pub async fn set_mute(&self, mute: bool) -> Result<()> {
+        self.set_channel_mute(Channel::Master, mute).await
+    }
+    pub async fn channel_mute(&self, channel: Channel) -> Result<bool> {
+        let args = args! { "InstanceID": 0, "Channel": channel };
         self.action(RENDERING_CONTROL, "GetMute", args)
             .await?
             .extract("CurrentMute")
             .and_then(utils::parse_bool)
     }
-    pub async fn set_mute(&self, mute: bool) -> Result<()> {
-        let args = args! { "InstanceID": 0, "Channel": "Master", "DesiredMute": mute as u8 };
+    pub async fn set_channel_mute(&self, channel: Channel, mute: bool) -> Result<()> {
+        let args = args! { "InstanceID": 0, "Channel": channel, "DesiredMute": mute as u8 };
         self.action(RENDERING_CONTROL, "SetMute", args)
             .await
             .map(drop)
@@ -314,22 +854,254 @@ impl Speaker {
             .map(drop)
     }
 
+    // AUDIO_IN
+
+    /// Returns the state of the line-in.
+    /// As the speaker only reports whether a cable is plugged in through events, this briefly
+    /// [subscribes](struct.Speaker.html#method.subscribe) to the `AudioIn` service, see
+    /// [line_in_connected](struct.Speaker.html#method.line_in_connected).
+    pub async fn line_in(&self) -> Result<LineIn> {
+        let ((name, icon), (left_level, right_level), connected) = try_join3(
+            self.line_in_attributes(),
+            self.line_in_level(),
+            self.line_in_connected(),
+        )
+        .await?;
+
+        Ok(LineIn {
+            name,
+            icon,
+            left_level,
+            right_level,
+            connected,
+        })
+    }
+    /// Returns the name and icon of the line-in as shown in the app.
+    pub async fn line_in_attributes(&self) -> Result<(String, String)> {
+        let mut map = self.action(AUDIO_IN, "GetAudioInputAttributes", "").await?;
+        let name = map.remove("CurrentName").unwrap_or_default();
+        let icon = map.remove("CurrentIcon").unwrap_or_default();
+        Ok((name, icon))
+    }
+    /// Returns the input level of the left and the right channel of the line-in.
+    pub async fn line_in_level(&self) -> Result<(i32, i32)> {
+        let mut map = self.action(AUDIO_IN, "GetLineInLevel", "").await?;
+        let mut level = |key| -> Result<i32> {
+            map.extract(key)?
+                .parse()
+                .map_err(|e| rupnp::Error::invalid_response(e).into())
+        };
+        Ok((
+            level("CurrentLeftLineInLevel")?,
+            level("CurrentRightLineInLevel")?,
+        ))
+    }
+    /// Whether a cable is plugged into the line-in.
+    /// There is no action for this, so it briefly [subscribes](struct.Speaker.html#method.subscribe)
+    /// to the `AudioIn` service and reads the initial event.
+    pub async fn line_in_connected(&self) -> Result<bool> {
+        match self.initial_event(Service::AudioIn).await? {
+            Event::AudioIn(change) => change.connected(),
+            _ => None,
+        }
+        .ok_or(Error::MissingInitialEvent(Service::AudioIn))
+    }
+    /// Sets the name and icon of the line-in as shown in the app.
+    pub async fn set_line_in_attributes(&self, name: &str, icon: &str) -> Result<()> {
+        let args = args! { "DesiredName": utils::escape(name), "DesiredIcon": utils::escape(icon) };
+        self.action(AUDIO_IN, "SetAudioInputAttributes", args)
+            .await
+            .map(drop)
+    }
+    pub async fn set_line_in_level(&self, left_level: i32, right_level: i32) -> Result<()> {
+        let args =
+            args! { "DesiredLeftLineInLevel": left_level, "DesiredRightLineInLevel": right_level };
+        self.action(AUDIO_IN, "SetLineInLevel", args)
+            .await
+            .map(drop)
+    }
+    /// Starts streaming the line-in to the group with the coordinator `coordinator_uuid`.
+    /// Returns the transport settings of the stream.
+    pub async fn start_line_in_transmission(&self, coordinator_uuid: &str) -> Result<String> {
+        let args = args! { "CoordinatorID": coordinator_uuid };
+        self.action(AUDIO_IN, "StartTransmissionToGroup", args)
+            .await
+            .map(|mut map| map.remove("CurrentTransportSettings").unwrap_or_default())
+    }
+    pub async fn stop_line_in_transmission(&self, coordinator_uuid: &str) -> Result<()> {
+        let args = args! { "CoordinatorID": coordinator_uuid };
+        self.action(AUDIO_IN, "StopTransmissionToGroup", args)
+            .await
+            .map(drop)
+    }
+    /// Plays the line-in of `source`, which may be another speaker or this one.
+    pub async fn play_line_in_from(&self, source: &Speaker) -> Result<()> {
+        let source = TransportSource::LineIn(source.uuid().await?);
+        self.set_transport_source(&source).await?;
+        self.play().await
+    }
+
+    // GROUP_RENDERING_CONTROL
+    // these actions are rejected by speakers which aren't the coordinator of their group
+
+    /// Returns the coordinator of the group this speaker is in, which may be the speaker itself.
+    pub(crate) async fn coordinator(&self) -> Result<Speaker> {
+        let topology = self._zone_group_state().await?;
+        let (coordinator, speakers) = topology
+            .iter()
+            .find(|(_, speakers)| {
+                speakers
+                    .iter()
+                    .any(|speaker_info| self.0.url() == speaker_info.location())
+            })
+            .ok_or(Error::SpeakerNotIncludedInOwnZoneGroupState)?;
+
+        let location = speakers
+            .iter()
+            .find(|speaker_info| speaker_info.uuid().eq_ignore_ascii_case(coordinator))
+            .map(SpeakerInfo::location)
+            .ok_or(Error::SpeakerNotIncludedInOwnZoneGroupState)?;
+
+        self.speaker_at(location).await
+    }
+
+    /// Returns the speaker at `location` as listed in the zone group state, which may be the
+    /// speaker itself.
+    async fn speaker_at(&self, location: &str) -> Result<Speaker> {
+        if self.0.url() == location {
+            return Ok(self.clone());
+        }
+
+        let device = Device::from_url(location.parse()?).await?;
+        Speaker::from_device(device).ok_or(Error::GetZoneGroupStateReturnedNonSonos)
+    }
+
+    async fn group_action(&self, action: &str, payload: &str) -> Result<HashMap<String, String>> {
+        self.coordinator()
+            .await?
+            .action(GROUP_RENDERING_CONTROL, action, payload)
+            .await
+    }
+
+    pub async fn group_volume(&self) -> Result<u16> {
+        self.group_action("GetGroupVolume", DEFAULT_ARGS)
+            .await?
+            .extract("CurrentVolume")
+            .and_then(|x| {
+                x.parse()
+                    .map_err(|e| rupnp::Error::invalid_response(e).into())
+            })
+    }
+    /// Sets the volume of the whole group, keeping the volume ratios between the speakers.
+    pub async fn set_group_volume(&self, volume: u16) -> Result<()> {
+        let args = args! { "InstanceID": 0, "DesiredVolume": volume };
+        self.group_action("SetGroupVolume", args).await.map(drop)
+    }
+    pub async fn set_group_volume_relative(&self, adjustment: i16) -> Result<u16> {
+        let args = args! { "InstanceID": 0, "Adjustment": adjustment };
+        self.group_action("SetRelativeGroupVolume", args)
+            .await?
+            .extract("NewVolume")
+            .and_then(|x| {
+                x.parse()
+                    .map_err(|e| rupnp::Error::invalid_response(e).into())
+            })
+    }
+
+    pub async fn group_mute(&self) -> Result<bool> {
+        self.group_action("GetGroupMute", DEFAULT_ARGS)
+            .await?
+            .extract("CurrentMute")
+            .and_then(utils::parse_bool)
+    }
+    pub async fn set_group_mute(&self, mute: bool) -> Result<()> {
+        let args = args! { "InstanceID": 0, "DesiredMute": mute as u8 };
+        self.group_action("SetGroupMute", args).await.map(drop)
+    }
+
+    /// Stores the volume ratios between the speakers of the group, which are then kept by
+    /// [set_group_volume](struct.Speaker.html#method.set_group_volume).
+    /// This happens automatically on the first group volume change after the group was formed
+    /// or a speaker's volume was changed individually.
+    pub async fn snapshot_group_volume(&self) -> Result<()> {
+        self.group_action("SnapshotGroupVolume", DEFAULT_ARGS)
+            .await
+            .map(drop)
+    }
+
+    pub async fn eq(&self, setting: EqSetting) -> Result<i16> {
+        let args = args! { "InstanceID": 0, "EQType": setting };
+        self.action(RENDERING_CONTROL, "GetEQ", args)
+            .await?
+            .extract("CurrentValue")
+            .and_then(|x| {
+                x.parse()
+                    .map_err(|e| rupnp::Error::invalid_response(e).into())
+            })
+    }
+    /// Returns `Error::ValueOutOfRange` without contacting the speaker when `value` isn't in the
+    /// [range](enum.EqSetting.html#method.range) of the setting.
+    pub async fn set_eq(&self, setting: EqSetting, value: i16) -> Result<()> {
+        let range = setting.range();
+        if !range.contains(&value) {
+            return Err(Error::ValueOutOfRange {
+                name: setting.to_string(),
+                value: value.into(),
+                min: (*range.start()).into(),
+                max: (*range.end()).into(),
+            });
+        }
+
+        let args = args! { "InstanceID": 0, "EQType": setting, "DesiredValue": value };
+        self.action(RENDERING_CONTROL, "SetEQ", args)
+            .await
+            .map(drop)
+    }
+    /// Resets bass, treble, loudness and the balance to their defaults.
+    pub async fn reset_basic_eq(&self) -> Result<()> {
+        self.action(RENDERING_CONTROL, "ResetBasicEQ", DEFAULT_ARGS)
+            .await
+            .map(drop)
+    }
+    /// Resets an extended equalizer setting to its default.
+    pub async fn reset_ext_eq(&self, setting: EqSetting) -> Result<()> {
+        let args = args! { "InstanceID": 0, "EQType": setting };
+        self.action(RENDERING_CONTROL, "ResetExtEQ", args)
+            .await
+            .map(drop)
+    }
+
     // Queue
     pub async fn queue(&self) -> Result<Vec<Track>> {
-        let args = args! { "QueueID": 0, "StartingIndex": 0, "RequestedCount": std::u32::MAX };
-        let result = self
-            .action(QUEUE, "Browse", args)
-            .await?
-            .extract("Result")?;
+        self.queue_stream().try_collect().await
+    }
+    /// Returns up to `count` tracks of the queue starting at the index `start`, which is 0 for
+    /// the first track. A `count` of `0` returns all remaining tracks.
+    pub async fn queue_page(&self, start: u32, count: u32) -> Result<QueuePage> {
+        let args = args! { "QueueID": 0, "StartingIndex": start, "RequestedCount": count };
+        let map = self.action(QUEUE, "Browse", args).await?;
+        QueuePage::from_response(map)
+    }
+    /// Returns the tracks of the queue, fetching them in chunks as the stream is polled.
+    /// The stream ends with `Error::QueueChanged` when the queue is changed in the meantime.
+    pub fn queue_stream(&self) -> impl Stream<Item = Result<Track>> + '_ {
+        stream::try_unfold(Some((0, None)), move |state| async move {
+            let (start, update_id) = match state {
+                Some(state) => state,
+                None => return Ok::<_, Error>(None),
+            };
+            let page = self.queue_page(start, PAGE_SIZE).await?;
+            if update_id.is_some_and(|update_id| update_id != page.update_id()) {
+                return Err(Error::QueueChanged);
+            }
 
-        Document::parse(&result)?
-            .root()
-            .first_element_child()
-            .ok_or_else(|| rupnp::Error::ParseError("Queue Response contains no children"))?
-            .children()
-            .filter(roxmltree::Node::is_element)
-            .map(Track::from_xml)
-            .collect()
+            let next = start + page.number_returned();
+            let next = Some((next, Some(page.update_id())))
+                .filter(|&(next, _)| page.number_returned() > 0 && next < page.total_matches());
+            let tracks = page.into_tracks().into_iter().map(Ok);
+            Ok(Some((stream::iter(tracks), next)))
+        })
+        .try_flatten()
     }
 
     // TODO test the next ones
@@ -340,52 +1112,472 @@ impl Speaker {
             .map(drop)
     }
 
+    async fn enqueue(
+        &self,
+        uri: &str,
+        metadata: &str,
+        track_no: u32,
+        as_next: bool,
+    ) -> Result<EnqueueResult> {
+        let args = args! {
+            "InstanceID": 0,
+            "EnqueuedURI": utils::escape(uri),
+            "EnqueuedURIMetaData": utils::escape(metadata),
+            "DesiredFirstTrackNumberEnqueued": track_no,
+            "EnqueueAsNext": as_next as u8
+        };
+        let map = self.action(AV_TRANSPORT, "AddURIToQueue", args).await?;
+        EnqueueResult::from_response(map)
+    }
+
     /// Enqueues a track at the end of the queue.
-    pub async fn queue_end(&self, uri: &str, metadata: &str) -> Result<()> {
-        let args = args! { "InstanceID": 0, "EnqueuedURI": uri, "EnqueuedURIMetaData": metadata, "DesiredFirstTrackNumberEnqueued": 0, "EnqueueAsNext": 0 };
-        self.action(AV_TRANSPORT, "AddURIToQueue", args)
+    /// `uri` and `metadata` are escaped by this method, so pass them unescaped.
+    pub async fn queue_end(&self, uri: &str, metadata: &str) -> Result<EnqueueResult> {
+        self.enqueue(uri, metadata, 0, false).await
+    }
+
+    /// Enqueues a track as the next one.
+    /// `uri` and `metadata` are escaped, see [queue_end](struct.Speaker.html#method.queue_end).
+    pub async fn queue_next(&self, uri: &str, metadata: &str) -> Result<EnqueueResult> {
+        self.enqueue(uri, metadata, 0, true).await
+    }
+
+    /// Enqueues a track so that it gets the track number `track_no`. The first track number is 1.
+    pub async fn queue_at(
+        &self,
+        track_no: u32,
+        uri: &str,
+        metadata: &str,
+    ) -> Result<EnqueueResult> {
+        self.enqueue(uri, metadata, track_no, false).await
+    }
+
+    /// Enqueues a track as the next one and starts playing it from the queue.
+    pub async fn play_now(&self, uri: &str, metadata: &str) -> Result<()> {
+        let enqueued = self.queue_next(uri, metadata).await?;
+
+        let queue = TransportSource::Queue(format!("{}#0", self.uuid().await?));
+        self.set_transport_source(&queue).await?;
+        self.seek_track(enqueued.first_track_number()).await?;
+        self.play().await
+    }
+
+    pub async fn clear_queue(&self) -> Result<()> {
+        self.action(AV_TRANSPORT, "RemoveAllTracksFromQueue", DEFAULT_ARGS)
             .await
             .map(drop)
     }
 
-    /// Enqueues a track as the next one.
-    pub async fn queue_next(&self, uri: &str, metadata: &str) -> Result<()> {
-        let args = args! { "InstanceID": 0, "EnqueuedURI": uri, "EnqueuedURIMetaData": metadata, "DesiredFirstTrackNumberEnqueued": 0, "EnqueueAsNext": 1 };
-        self.action(AV_TRANSPORT, "AddURIToQueue", args)
+    // The following queue edits take the `update_id` of the queue as returned by
+    // `queue_page`, and fail if the queue was changed in the meantime.
+    // Track indices start at 0, like in `queue_page`.
+
+    /// Moves the tracks in `range` in front of the track at `insert_before`.
+    /// Returns the new update ID of the queue.
+    pub async fn move_tracks(
+        &self,
+        range: Range<u32>,
+        insert_before: u32,
+        update_id: u32,
+    ) -> Result<u32> {
+        let args = args! {
+            "QueueID": 0,
+            "StartingIndex": range.start + 1,
+            "NumberOfTracks": track_count(&range)?,
+            "InsertBefore": insert_before + 1,
+            "UpdateID": update_id
+        };
+        self.action(QUEUE, "ReorderTracks", args)
+            .await?
+            .extract("NewUpdateID")?
+            .parse()
+            .map_err(|e| rupnp::Error::invalid_response(e).into())
+    }
+    /// Removes the tracks in `range` and returns the new update ID of the queue.
+    pub async fn remove_tracks(&self, range: Range<u32>, update_id: u32) -> Result<u32> {
+        let args = args! {
+            "InstanceID": 0,
+            "UpdateID": update_id,
+            "StartingIndex": range.start + 1,
+            "NumberOfTracks": track_count(&range)?
+        };
+        self.action(AV_TRANSPORT, "RemoveTrackRangeFromQueue", args)
+            .await?
+            .extract("NewUpdateID")?
+            .parse()
+            .map_err(|e| rupnp::Error::invalid_response(e).into())
+    }
+    /// Enqueues the `(uri, metadata)` pairs at the end of the queue, 16 tracks per request.
+    /// Returns the new update ID of the queue.
+    pub async fn queue_many(&self, tracks: &[(&str, &str)], update_id: u32) -> Result<u32> {
+        let mut update_id = update_id;
+
+        for chunk in tracks.chunks(MAX_URIS_PER_ENQUEUE) {
+            let uris: Vec<_> = chunk.iter().map(|(uri, _)| utils::escape(uri)).collect();
+            let metadata: Vec<_> = chunk.iter().map(|(_, meta)| utils::escape(meta)).collect();
+            let args = args! {
+                "InstanceID": 0,
+                "UpdateID": update_id,
+                "NumberOfURIs": chunk.len(),
+                "EnqueuedURIs": uris.join(" "),
+                "EnqueuedURIsMetaData": metadata.join(" "),
+                "ContainerURI": "",
+                "ContainerMetaData": "",
+                "DesiredFirstTrackNumberEnqueued": 0,
+                "EnqueueAsNext": 0
+            };
+            update_id = self
+                .action(AV_TRANSPORT, "AddMultipleURIsToQueue", args)
+                .await?
+                .extract("NewUpdateID")?
+                .parse()
+                .map_err(rupnp::Error::invalid_response)?;
+        }
+
+        Ok(update_id)
+    }
+    /// Replaces all tracks of the queue with the `(uri, metadata)` pairs.
+    /// The first 16 tracks replace the queue in a single request, the rest are enqueued afterwards.
+    /// Returns the new update ID of the queue.
+    pub async fn replace_queue(&self, tracks: &[(&str, &str)], update_id: u32) -> Result<u32> {
+        if tracks.is_empty() {
+            let args = args! { "QueueID": 0, "UpdateID": update_id };
+            return self
+                .action(QUEUE, "RemoveAllTracks", args)
+                .await?
+                .extract("NewUpdateID")?
+                .parse()
+                .map_err(|e| rupnp::Error::invalid_response(e).into());
+        }
+
+        let (first, rest) = tracks.split_at(tracks.len().min(MAX_URIS_PER_ENQUEUE));
+        let uris_and_metadata: Vec<_> = first
+            .iter()
+            .flat_map(|(uri, meta)| vec![utils::escape(uri), utils::escape(meta)])
+            .collect();
+        let args = args! {
+            "QueueID": 0,
+            "UpdateID": update_id,
+            "ContainerURI": "",
+            "ContainerMetaData": "",
+            "CurrentTrackIndex": 0,
+            "NewCurrentTrackIndices": "",
+            "NumberOfURIs": first.len(),
+            "EnqueuedURIsAndMetaData": uris_and_metadata.join(" ")
+        };
+        let update_id = self
+            .action(QUEUE, "ReplaceAllTracks", args)
+            .await?
+            .extract("NewUpdateID")?
+            .parse()
+            .map_err(rupnp::Error::invalid_response)?;
+
+        self.queue_many(rest, update_id).await
+    }
+
+    // Sonos playlists
+    // edits take the `update_id` of the playlist as returned by `playlist_tracks`, and fail if it
+    // was changed in the meantime. Track indices start at 0.
+
+    /// Browses the children of `object_id` in the content directory in chunks.
+    /// Returns them together with the update ID of `object_id`.
+    async fn browse_children<T>(
+        &self,
+        object_id: &str,
+        parse: impl Fn(Node<'_, '_>) -> Result<T>,
+    ) -> Result<(Vec<T>, u32)> {
+        let mut items = Vec::new();
+        loop {
+            let args = args! {
+                "ObjectID": object_id,
+                "BrowseFlag": "BrowseDirectChildren",
+                "Filter": "*",
+                "StartingIndex": items.len(),
+                "RequestedCount": PAGE_SIZE,
+                "SortCriteria": ""
+            };
+            let mut map = self.action(CONTENT_DIRECTORY, "Browse", args).await?;
+            let result = map.extract("Result")?;
+            let total_matches: usize = map
+                .extract("TotalMatches")?
+                .parse()
+                .map_err(rupnp::Error::invalid_response)?;
+            let update_id = map
+                .extract("UpdateID")?
+                .parse()
+                .map_err(rupnp::Error::invalid_response)?;
+
+            let doc = Document::parse(&result)?;
+            let previous_len = items.len();
+            for node in doc.root_element().children().filter(Node::is_element) {
+                items.push(parse(node)?);
+            }
+
+            if items.len() == previous_len || items.len() >= total_matches {
+                return Ok((items, update_id));
+            }
+        }
+    }
+
+    async fn playlist(&self, id: &str) -> Result<SonosPlaylist> {
+        let args = args! {
+            "ObjectID": id,
+            "BrowseFlag": "BrowseMetadata",
+            "Filter": "*",
+            "StartingIndex": 0,
+            "RequestedCount": 1,
+            "SortCriteria": ""
+        };
+        let result = self
+            .action(CONTENT_DIRECTORY, "Browse", args)
+            .await?
+            .extract("Result")?;
+        let doc = Document::parse(&result)?;
+        SonosPlaylist::from_xml(utils::find_root_node(
+            &doc,
+            "container",
+            "Playlist Metadata",
+        )?)
+    }
+
+    pub async fn playlists(&self) -> Result<Vec<SonosPlaylist>> {
+        let (playlists, _) = self.browse_children("SQ:", SonosPlaylist::from_xml).await?;
+        Ok(playlists)
+    }
+    /// Returns the tracks of the playlist and the update ID needed to edit it.
+    pub async fn playlist_tracks(&self, playlist: &SonosPlaylist) -> Result<PlaylistTracks> {
+        let (tracks, update_id) = self.browse_children(playlist.id(), Track::from_xml).await?;
+        Ok(PlaylistTracks { tracks, update_id })
+    }
+    /// Saves the current queue as a new playlist.
+    pub async fn save_queue(&self, title: &str) -> Result<SonosPlaylist> {
+        let args = args! { "InstanceID": 0, "Title": utils::escape(title), "ObjectID": "" };
+        let id = self
+            .action(AV_TRANSPORT, "SaveQueue", args)
+            .await?
+            .extract("AssignedObjectID")?;
+        self.playlist(&id).await
+    }
+    /// Creates an empty playlist.
+    pub async fn create_playlist(&self, title: &str) -> Result<SonosPlaylist> {
+        let args = args! {
+            "InstanceID": 0,
+            "Title": utils::escape(title),
+            "EnqueuedURI": "",
+            "EnqueuedURIMetaData": ""
+        };
+        let id = self
+            .action(AV_TRANSPORT, "CreateSavedQueue", args)
+            .await?
+            .extract("AssignedObjectID")?;
+        self.playlist(&id).await
+    }
+    /// Appends a track to the playlist and returns its new update ID.
+    pub async fn add_to_playlist(
+        &self,
+        playlist: &SonosPlaylist,
+        uri: &str,
+        metadata: &str,
+        update_id: u32,
+    ) -> Result<u32> {
+        let args = args! {
+            "InstanceID": 0,
+            "ObjectID": playlist.id(),
+            "UpdateID": update_id,
+            "EnqueuedURI": utils::escape(uri),
+            "EnqueuedURIMetaData": utils::escape(metadata),
+            "AddAtIndex": u32::MAX
+        };
+        self.action(AV_TRANSPORT, "AddURIToSavedQueue", args)
+            .await?
+            .extract("NewUpdateID")?
+            .parse()
+            .map_err(|e| rupnp::Error::invalid_response(e).into())
+    }
+    async fn reorder_playlist(
+        &self,
+        playlist: &SonosPlaylist,
+        tracks: &[u32],
+        new_positions: &[u32],
+        update_id: u32,
+    ) -> Result<u32> {
+        let join = |indices: &[u32]| {
+            indices
+                .iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let args = args! {
+            "InstanceID": 0,
+            "ObjectID": playlist.id(),
+            "UpdateID": update_id,
+            "TrackList": join(tracks),
+            "NewPositionList": join(new_positions)
+        };
+        self.action(AV_TRANSPORT, "ReorderTracksInSavedQueue", args)
+            .await?
+            .extract("NewUpdateID")?
+            .parse()
+            .map_err(|e| rupnp::Error::invalid_response(e).into())
+    }
+    /// Moves the track at `from` to `to` and returns the new update ID of the playlist.
+    pub async fn move_playlist_track(
+        &self,
+        playlist: &SonosPlaylist,
+        from: u32,
+        to: u32,
+        update_id: u32,
+    ) -> Result<u32> {
+        self.reorder_playlist(playlist, &[from], &[to], update_id)
+            .await
+    }
+    /// Removes the tracks at `tracks` and returns the new update ID of the playlist.
+    pub async fn remove_from_playlist(
+        &self,
+        playlist: &SonosPlaylist,
+        tracks: &[u32],
+        update_id: u32,
+    ) -> Result<u32> {
+        self.reorder_playlist(playlist, tracks, &[], update_id)
+            .await
+    }
+    pub async fn delete_playlist(&self, playlist: &SonosPlaylist) -> Result<()> {
+        let args = args! { "ObjectID": playlist.id() };
+        self.action(CONTENT_DIRECTORY, "DestroyObject", args)
             .await
             .map(drop)
     }
+    /// Enqueues all tracks of the playlist at the end of the queue.
+    pub async fn queue_playlist(&self, playlist: &SonosPlaylist) -> Result<EnqueueResult> {
+        self.queue_end(playlist.uri(), &playlist.metadata()).await
+    }
 
-    pub async fn clear_queue(&self) -> Result<()> {
-        self.action(AV_TRANSPORT, "RemoveAllTracksFromQueue", DEFAULT_ARGS)
+    // ALARM_CLOCK
+
+    /// Returns all alarms of the household, not only those playing in this room.
+    pub async fn alarms(&self) -> Result<Vec<Alarm>> {
+        let alarm_list = self
+            .action(ALARM_CLOCK, "ListAlarms", "")
+            .await?
+            .extract("CurrentAlarmList")?;
+
+        Alarm::list_from_xml(&alarm_list)
+    }
+
+    /// Creates a new alarm and returns its ID.
+    pub async fn create_alarm(&self, alarm: &Alarm) -> Result<u32> {
+        self.action(ALARM_CLOCK, "CreateAlarm", &alarm.args())
+            .await?
+            .extract("AssignedID")
+            .and_then(|x| {
+                x.parse()
+                    .map_err(|e| rupnp::Error::invalid_response(e).into())
+            })
+    }
+
+    /// Saves the changes to an alarm returned by [alarms](struct.Speaker.html#method.alarms).
+    ///
+    /// Returns `Error::AlarmWithoutId` for alarms which were created locally with
+    /// [Alarm::new](struct.Alarm.html#method.new).
+    pub async fn update_alarm(&self, alarm: &Alarm) -> Result<()> {
+        let id = alarm.id().ok_or(Error::AlarmWithoutId)?;
+        let args = format!("{}{}", args! { "ID": id }, alarm.args());
+        self.action(ALARM_CLOCK, "UpdateAlarm", &args)
+            .await
+            .map(drop)
+    }
+
+    pub async fn delete_alarm(&self, id: u32) -> Result<()> {
+        self.action(ALARM_CLOCK, "DestroyAlarm", args! { "ID": id })
+            .await
+            .map(drop)
+    }
+
+    /// Returns the current UTC and local time of the household.
+    pub async fn time_now(&self) -> Result<HouseholdTime> {
+        let mut map = self.action(ALARM_CLOCK, "GetTimeNow", "").await?;
+
+        Ok(HouseholdTime {
+            utc_time: clock::parse_date_time(&map.extract("CurrentUTCTime")?)?,
+            local_time: clock::parse_date_time(&map.extract("CurrentLocalTime")?)?,
+            time_zone: map.extract("CurrentTimeZone")?,
+            time_generation: map
+                .extract("CurrentTimeGeneration")?
+                .parse()
+                .map_err(rupnp::Error::invalid_response)?,
+        })
+    }
+    /// Sets the clock of the household. `time_zone` is the zone `time` is in, use an empty string for
+    /// the household's own time zone.
+    pub async fn set_time_now(&self, time: DateTime, time_zone: &str) -> Result<()> {
+        let args = args! { "DesiredTime": time, "TimeZoneForDesiredTime": time_zone };
+        self.action(ALARM_CLOCK, "SetTimeNow", args).await.map(drop)
+    }
+
+    pub async fn time_zone(&self) -> Result<TimeZone> {
+        let mut map = self.action(ALARM_CLOCK, "GetTimeZoneAndRule", "").await?;
+
+        Ok(TimeZone {
+            index: map
+                .extract("Index")?
+                .parse()
+                .map_err(rupnp::Error::invalid_response)?,
+            auto_adjust_dst: utils::parse_bool(map.extract("AutoAdjustDst")?)?,
+            rule: map.extract("CurrentTimeZone")?,
+        })
+    }
+    /// `index` is the index into the list of time zones known to sonos.
+    pub async fn set_time_zone(&self, index: i32, auto_adjust_dst: bool) -> Result<()> {
+        let args = args! { "Index": index, "AutoAdjustDst": auto_adjust_dst as u8 };
+        self.action(ALARM_CLOCK, "SetTimeZone", args)
+            .await
+            .map(drop)
+    }
+
+    /// The NTP server the household synchronizes its clock with.
+    pub async fn time_server(&self) -> Result<String> {
+        self.action(ALARM_CLOCK, "GetTimeServer", "")
+            .await?
+            .extract("CurrentTimeServer")
+    }
+    pub async fn set_time_server(&self, time_server: &str) -> Result<()> {
+        let args = args! { "DesiredTimeServer": time_server };
+        self.action(ALARM_CLOCK, "SetTimeServer", args)
             .await
             .map(drop)
     }
 
+    /// The format times and dates are displayed in by the sonos apps.
+    pub async fn time_and_date_format(&self) -> Result<(TimeFormat, DateFormat)> {
+        let mut map = self.action(ALARM_CLOCK, "GetFormat", "").await?;
+
+        let time_format = map
+            .extract("CurrentTimeFormat")?
+            .parse()
+            .map_err(rupnp::Error::invalid_response)?;
+        let date_format = map
+            .extract("CurrentDateFormat")?
+            .parse()
+            .map_err(rupnp::Error::invalid_response)?;
+        Ok((time_format, date_format))
+    }
+    pub async fn set_time_and_date_format(
+        &self,
+        time_format: TimeFormat,
+        date_format: DateFormat,
+    ) -> Result<()> {
+        let args = args! { "DesiredTimeFormat": time_format, "DesiredDateFormat": date_format };
+        self.action(ALARM_CLOCK, "SetFormat", args).await.map(drop)
+    }
+
     pub(crate) async fn _zone_group_state(&self) -> Result<Vec<(String, Vec<SpeakerInfo>)>> {
         let state = self
             .action(ZONE_GROUP_TOPOLOGY, "GetZoneGroupState", "")
             .await?
             .extract("ZoneGroupState")?;
 
-        let doc = Document::parse(&state)?;
-        let state = utils::find_root_node(&doc, "ZoneGroups", "Zone Group Topology")?;
-
-        state
-            .children()
-            .filter(Node::is_element)
-            .filter(|c| c.tag_name().name().eq_ignore_ascii_case("ZoneGroup"))
-            .map(|group| {
-                let coordinator = utils::find_node_attribute(group, "Coordinator")?.to_string();
-                let members = group
-                    .children()
-                    .filter(Node::is_element)
-                    .filter(|c| c.tag_name().name().eq_ignore_ascii_case("ZoneGroupMember"))
-                    .map(SpeakerInfo::from_xml)
-                    .collect::<Result<Vec<_>>>()?;
-                Ok((coordinator, members))
-            })
-            .collect()
+        parse_zone_group_state(&state)
     }
 
     /// Returns all groups in the system as a map from the group coordinators UUID to a list of [Speaker Info](struct.SpeakerInfo.html)s.
@@ -396,10 +1588,8 @@ impl Speaker {
     /// Form a group with a player.
     /// The UUID should look like this: 'RINCON_000E5880EA7601400'.
     async fn join_uuid(&self, uuid: &str) -> Result<()> {
-        let args = args! { "InstanceID": 0, "CurrentURI": format!("x-rincon:{}", uuid), "CurrentURIMetaData": "" };
-        self.action(AV_TRANSPORT, "SetAVTransportURI", args)
-            .await
-            .map(drop)
+        let source = TransportSource::Grouped(uuid.to_string());
+        self.set_transport_source(&source).await
     }
 
     /// Form a group with a player.
@@ -436,6 +1626,9 @@ impl Speaker {
     /// Set the transport URI for the speaker.
     /// Note that (at least my old Play:5 gen 1 speaker) will only accept urls without
     /// '?foo=bar' query parameters that end with '.mp3' or '.wav' etc.
+    ///
+    /// Unlike for the queue methods, `uri` and `metadata` are sent as they are and have to be
+    /// escaped by the caller.
     pub async fn set_transport_uri(&self, uri: &str, metadata: &str) -> Result<()> {
         let args = args! { "InstanceID": 0, "CurrentURI": uri, "CurrentURIMetaData": metadata };
         self.action(AV_TRANSPORT, "SetAVTransportURI", args)
@@ -451,6 +1644,27 @@ impl Speaker {
             .remove("CurrentURI");
         Ok(uri)
     }
+    pub async fn media_info(&self) -> Result<MediaInfo> {
+        let map = self
+            .action(AV_TRANSPORT, "GetMediaInfo", DEFAULT_ARGS)
+            .await?;
+        MediaInfo::from_response(map)
+    }
+    /// The media the speaker can play from and record to.
+    pub async fn device_capabilities(&self) -> Result<DeviceCapabilities> {
+        self.action(AV_TRANSPORT, "GetDeviceCapabilities", DEFAULT_ARGS)
+            .await
+            .map(DeviceCapabilities::from_response)
+    }
+    /// Get the [source](enum.TransportSource.html) the speaker is currently playing from.
+    pub async fn transport_source(&self) -> Result<Option<TransportSource>> {
+        let uri = self.transport_uri().await?;
+        Ok(uri.and_then(|uri| uri.parse().ok()))
+    }
+    pub async fn set_transport_source(&self, source: &TransportSource) -> Result<()> {
+        self.set_transport_uri(&utils::escape(&source.to_string()), "")
+            .await
+    }
 
     #[allow(unused)]
     /// returns a map of lowercase service name to a tuple of (sid, capabilities, stype)
@@ -498,6 +1712,35 @@ impl Speaker {
         snapshot.apply(self).await
     }
 
+    /// Subscribe to the events of one of the speaker's services.
+    /// The returned [Subscription](struct.Subscription.html) is a stream of
+    /// [Event](enum.Event.html)s, which is kept alive in the background and unsubscribes when dropped.
+    ///
+    /// ```rust,no_run
+    /// # use futures::prelude::*;
+    /// # async fn f(speaker: sonor::Speaker) -> Result<(), sonor::Error> {
+    /// let mut events = speaker.subscribe(sonor::Service::RenderingControl).await?;
+    /// while let Some(event) = events.try_next().await? {
+    ///     println!("{:?}", event);
+    /// }
+    /// # Ok(())
+    /// # };
+    /// ```
+    pub async fn subscribe(&self, service: Service) -> Result<Subscription> {
+        Subscription::new(self, service).await
+    }
+    /// Subscribes to `service` and returns the initial event containing its full state.
+    async fn initial_event(&self, service: Service) -> Result<Event> {
+        let mut events = self.subscribe(service).await?;
+
+        let event = tokio::time::timeout(Duration::from_secs(5), events.try_next()).await;
+        match event {
+            Ok(Ok(Some(event))) => Ok(event),
+            Ok(Err(err)) => Err(err),
+            _ => Err(Error::MissingInitialEvent(service)),
+        }
+    }
+
     /// Execute some UPnP Action on the device.
     /// A list of services, devices and actions of the 'ZonePlayer:1' standard can be found [here](https://github.com/jakobhellermann/sonos/tree/master/zoneplayer).
     pub async fn action(
@@ -518,3 +1761,52 @@ impl Speaker {
             .await?)
     }
 }
+
+/// The number of tracks in `range`, rejecting empty and inverted ranges.
+fn track_count(range: &Range<u32>) -> Result<u32> {
+    if range.start >= range.end {
+        return Err(Error::EmptyRange {
+            start: range.start,
+            end: range.end,
+        });
+    }
+    Ok(range.end - range.start)
+}
+
+pub(crate) fn parse_zone_group_state(state: &str) -> Result<Vec<(String, Vec<SpeakerInfo>)>> {
+    let doc = Document::parse(state)?;
+    let state = utils::find_root_node(&doc, "ZoneGroups", "Zone Group Topology")?;
+
+    state
+        .children()
+        .filter(Node::is_element)
+        .filter(|c| c.tag_name().name().eq_ignore_ascii_case("ZoneGroup"))
+        .map(|group| {
+            let coordinator = utils::find_node_attribute(group, "Coordinator")?.to_string();
+            let members = group
+                .children()
+                .filter(Node::is_element)
+                .filter(|c| c.tag_name().name().eq_ignore_ascii_case("ZoneGroupMember"))
+                .map(SpeakerInfo::from_xml)
+                .collect::<Result<Vec<_>>>()?;
+            Ok((coordinator, members))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn track_ranges() {
+        assert_eq!(track_count(&(0..3)).unwrap(), 3);
+        assert!(matches!(
+            track_count(&(2..2)),
+            Err(Error::EmptyRange { start: 2, end: 2 })
+        ));
+        #[allow(clippy::reversed_empty_ranges)]
+        let inverted = 5..1;
+        assert!(track_count(&inverted).is_err());
+    }
+}
@@ -0,0 +1,451 @@
+use crate::{
+    last_change::{AvTransportChange, RenderingControlChange},
+    line_in::LineInChange,
+    speaker::{self, Speaker, AUDIO_IN, AV_TRANSPORT, RENDERING_CONTROL, ZONE_GROUP_TOPOLOGY},
+    Error, Result, SpeakerInfo,
+};
+use futures_util::{stream::BoxStream, Stream, StreamExt};
+use rupnp::{http::Uri, ssdp::URN};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc, watch},
+    time::Instant,
+};
+
+/// How long a subscription lasts before it has to be renewed.
+const SUBSCRIPTION_TIMEOUT_SECS: u32 = 300;
+/// How long to wait before trying to resubscribe after it failed, e.g. while the speaker reboots.
+const RESUBSCRIBE_RETRY: Duration = Duration::from_secs(10);
+
+/// The services of a speaker which can be subscribed to using
+/// [Speaker::subscribe](struct.Speaker.html#method.subscribe).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Service {
+    /// Playback state, current track and transport settings.
+    AVTransport,
+    /// Volume, mute and equalizer settings.
+    RenderingControl,
+    /// Grouping of the speakers in the household.
+    ZoneGroupTopology,
+    /// The line-in of the speaker.
+    AudioIn,
+}
+
+impl Service {
+    fn urn(self) -> &'static URN {
+        match self {
+            Service::AVTransport => AV_TRANSPORT,
+            Service::RenderingControl => RENDERING_CONTROL,
+            Service::ZoneGroupTopology => ZONE_GROUP_TOPOLOGY,
+            Service::AudioIn => AUDIO_IN,
+        }
+    }
+}
+
+/// A change notification sent by the speaker for a subscribed [Service](enum.Service.html).
+#[derive(Debug)]
+pub enum Event {
+    /// The changed state of the AVTransport service.
+    AVTransport(Box<AvTransportChange>),
+    /// The changed state of the RenderingControl service.
+    RenderingControl(RenderingControlChange),
+    /// The new zone group state, in the same format as returned by
+    /// [zone_group_state](struct.Speaker.html#method.zone_group_state).
+    ZoneGroupTopology(HashMap<String, Vec<SpeakerInfo>>),
+    /// The changed state of the line-in.
+    AudioIn(LineInChange),
+}
+
+impl Event {
+    /// Returns `None` for events which don't carry the information of the typed variant,
+    /// e.g. events without a `LastChange` or `ZoneGroupState`.
+    fn from_properties(
+        service: Service,
+        mut properties: HashMap<String, String>,
+    ) -> Result<Option<Self>> {
+        let event = match service {
+            Service::AVTransport => match properties.remove("LastChange") {
+                Some(last_change) => {
+                    Event::AVTransport(Box::new(AvTransportChange::from_last_change(&last_change)?))
+                }
+                None => return Ok(None),
+            },
+            Service::RenderingControl => match properties.remove("LastChange") {
+                Some(last_change) => {
+                    Event::RenderingControl(RenderingControlChange::from_last_change(&last_change)?)
+                }
+                None => return Ok(None),
+            },
+            Service::ZoneGroupTopology => match properties.remove("ZoneGroupState") {
+                Some(state) => Event::ZoneGroupTopology(
+                    speaker::parse_zone_group_state(&state)?
+                        .into_iter()
+                        .collect(),
+                ),
+                None => return Ok(None),
+            },
+            Service::AudioIn => Event::AudioIn(LineInChange::from_properties(properties)?),
+        };
+        Ok(Some(event))
+    }
+}
+
+/// A stream of [Event](enum.Event.html)s for one service of a speaker.
+/// It is obtained by calling [Speaker::subscribe](struct.Speaker.html#method.subscribe).
+///
+/// The subscription is kept alive by a background task, independently of how often the stream
+/// is polled. When the speaker rejects a renewal, e.g. after a reboot, the task subscribes again
+/// and the stream continues with the initial event of the new subscription.
+/// The speaker is unsubscribed from when the stream gets dropped.
+#[derive(Debug)]
+pub struct Subscription {
+    kind: Service,
+    sid: watch::Receiver<String>,
+    events: mpsc::UnboundedReceiver<Result<HashMap<String, String>>>,
+}
+
+type PropertyStream = BoxStream<'static, Result<HashMap<String, String>, rupnp::Error>>;
+
+impl Subscription {
+    pub(crate) async fn new(speaker: &Speaker, kind: Service) -> Result<Self> {
+        let service = speaker
+            .device()
+            .find_service(kind.urn())
+            .ok_or_else(|| Error::MissingServiceForSubscription {
+                service: kind.urn().clone(),
+            })?
+            .clone();
+        let url = speaker.device().url().clone();
+
+        let (sid, stream) = service.subscribe(&url, SUBSCRIPTION_TIMEOUT_SECS).await?;
+
+        let (sid_tx, sid_rx) = watch::channel(sid);
+        let (events_tx, events_rx) = mpsc::unbounded_channel();
+        let task = SubscriptionTask {
+            service,
+            url,
+            stream: Box::pin(stream),
+            sid: sid_tx,
+            events: events_tx,
+        };
+        tokio::spawn(task.run());
+
+        Ok(Self {
+            kind,
+            sid: sid_rx,
+            events: events_rx,
+        })
+    }
+
+    /// The service this subscription delivers events for.
+    pub fn service(&self) -> Service {
+        self.kind
+    }
+
+    /// The subscription identifier assigned by the speaker.
+    /// It changes when the task keeping the subscription alive has to subscribe again.
+    pub fn sid(&self) -> String {
+        self.sid.borrow().clone()
+    }
+}
+
+// renew well before the speaker drops the subscription
+fn renew_interval() -> Duration {
+    Duration::from_secs(u64::from(SUBSCRIPTION_TIMEOUT_SECS) / 2)
+}
+
+/// Forwards the notifications of the speaker and renews the subscription until the
+/// [Subscription](struct.Subscription.html) is dropped.
+struct SubscriptionTask {
+    service: rupnp::Service,
+    url: Uri,
+    stream: PropertyStream,
+    sid: watch::Sender<String>,
+    events: mpsc::UnboundedSender<Result<HashMap<String, String>>>,
+}
+
+impl SubscriptionTask {
+    async fn run(mut self) {
+        let mut renew_at = Instant::now() + renew_interval();
+
+        loop {
+            tokio::select! {
+                _ = self.events.closed() => break,
+                properties = self.stream.next() => match properties {
+                    Some(properties) => {
+                        let _ = self.events.send(properties.map_err(Error::from));
+                    }
+                    None => break,
+                },
+                _ = tokio::time::sleep_until(renew_at) => {
+                    renew_at = match self.renew().await {
+                        Ok(()) => Instant::now() + renew_interval(),
+                        Err(err) => {
+                            let _ = self.events.send(Err(err));
+                            Instant::now() + RESUBSCRIBE_RETRY
+                        }
+                    };
+                }
+            }
+        }
+
+        let sid = self.sid.borrow().clone();
+        if let Err(err) = self.service.unsubscribe(&self.url, &sid).await {
+            log::warn!("failed to unsubscribe {}: {}", sid, err);
+        }
+    }
+
+    /// Renews the subscription, subscribing again if the speaker doesn't know it anymore.
+    async fn renew(&mut self) -> Result<()> {
+        let sid = self.sid.borrow().clone();
+        let err = match self
+            .service
+            .renew_subscription(&self.url, &sid, SUBSCRIPTION_TIMEOUT_SECS)
+            .await
+        {
+            Ok(()) => return Ok(()),
+            Err(err) => err,
+        };
+        log::info!("failed to renew {} ({}), subscribing again", sid, err);
+
+        let (sid, stream) = self
+            .service
+            .subscribe(&self.url, SUBSCRIPTION_TIMEOUT_SECS)
+            .await?;
+        self.stream = Box::pin(stream);
+        self.sid.send_replace(sid);
+        Ok(())
+    }
+}
+
+impl Stream for Subscription {
+    type Item = Result<Event>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            let properties = match futures_util::ready!(this.events.poll_recv(cx)) {
+                Some(Ok(properties)) => properties,
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => return Poll::Ready(None),
+            };
+
+            match Event::from_properties(this.kind, properties) {
+                Ok(Some(event)) => return Poll::Ready(Some(Ok(event))),
+                Ok(None) => continue,
+                Err(err) => return Poll::Ready(Some(Err(err))),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::TryStreamExt;
+    use rupnp::Device;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    const DEVICE_DESCRIPTION: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<root xmlns="urn:schemas-upnp-org:device-1-0">
+  <device>
+    <deviceType>urn:schemas-upnp-org:device:ZonePlayer:1</deviceType>
+    <friendlyName>127.0.0.1 - Sonos One</friendlyName>
+    <manufacturer>Sonos, Inc.</manufacturer>
+    <modelName>Sonos One</modelName>
+    <UDN>uuid:RINCON_000E58000000001400</UDN>
+    <serviceList>
+      <service>
+        <serviceType>urn:schemas-upnp-org:service:RenderingControl:1</serviceType>
+        <serviceId>urn:upnp-org:serviceId:RenderingControl</serviceId>
+        <controlURL>/MediaRenderer/RenderingControl/Control</controlURL>
+        <eventSubURL>/MediaRenderer/RenderingControl/Event</eventSubURL>
+        <SCPDURL>/xml/RenderingControl1.xml</SCPDURL>
+      </service>
+    </serviceList>
+  </device>
+</root>"#;
+
+    /// The requests the fake speaker received, as `(method, CALLBACK or SID header)`.
+    type Requests = mpsc::UnboundedSender<(String, String)>;
+
+    /// A stand-in for a speaker, serving its device description and answering (un)subscribe
+    /// requests. Renewals are answered with `renewal_status`.
+    async fn fake_speaker(renewal_status: &'static str, requests: Requests) -> Device {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let mut subscriptions = 0;
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let request = read_request(&mut stream).await;
+                let header = |name: &str| {
+                    request
+                        .lines()
+                        .find_map(|line| {
+                            let (key, value) = line.split_once(':')?;
+                            Some(value.trim()).filter(|_| key.eq_ignore_ascii_case(name))
+                        })
+                        .map(str::to_string)
+                };
+                let method = request.split(' ').next().unwrap_or_default().to_string();
+
+                let response = match (method.as_str(), header("CALLBACK")) {
+                    ("GET", _) => format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                        DEVICE_DESCRIPTION.len(),
+                        DEVICE_DESCRIPTION
+                    ),
+                    ("SUBSCRIBE", Some(callback)) => {
+                        subscriptions += 1;
+                        let _ = requests.send((method, callback));
+                        format!(
+                            "HTTP/1.1 200 OK\r\nSID: uuid:RINCON_000E58000000001400_sub{:010}\r\nTIMEOUT: Second-300\r\nContent-Length: 0\r\n\r\n",
+                            subscriptions
+                        )
+                    }
+                    ("SUBSCRIBE", None) => {
+                        let _ = requests.send((method, header("SID").unwrap()));
+                        format!("HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", renewal_status)
+                    }
+                    _ => {
+                        let _ = requests.send((method, header("SID").unwrap_or_default()));
+                        "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n".to_string()
+                    }
+                };
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+
+        let url = format!("http://{}/xml/device_description.xml", addr);
+        Device::from_url(url.parse().unwrap()).await.unwrap()
+    }
+
+    async fn read_request(stream: &mut TcpStream) -> String {
+        let mut request = Vec::new();
+        let mut buf = [0; 1024];
+        while !request.ends_with(b"\r\n\r\n") {
+            let n = stream.read(&mut buf).await.unwrap();
+            if n == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..n]);
+        }
+        String::from_utf8(request).unwrap()
+    }
+
+    /// Sends a NOTIFY request with the `properties` to the `callback` of a subscription.
+    async fn notify(callback: &str, sid: &str, properties: &str) {
+        let callback: Uri = callback
+            .trim_matches(|c| c == '<' || c == '>')
+            .parse()
+            .unwrap();
+        let body = format!(
+            "<e:propertyset xmlns:e=\"urn:schemas-upnp-org:event-1-0\">{}</e:propertyset>\n",
+            properties
+        );
+        let request = format!(
+            "NOTIFY / HTTP/1.1\r\nHOST: {}\r\nCONTENT-TYPE: text/xml\r\nNT: upnp:event\r\nNTS: upnp:propchange\r\nSID: {}\r\nSEQ: 0\r\nContent-Length: {}\r\n\r\n{}",
+            callback.authority().unwrap(),
+            sid,
+            body.len(),
+            body
+        );
+
+        let mut stream = TcpStream::connect(callback.authority().unwrap().as_str())
+            .await
+            .unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    }
+
+    /// Subscribes to the fake speaker. rupnp only listens for notifications on a private IPv4
+    /// interface, which is why the tests using it are ignored by default.
+    async fn subscribe(speaker: &Speaker) -> Subscription {
+        speaker
+            .subscribe(Service::RenderingControl)
+            .await
+            .expect("subscribing needs a private IPv4 interface")
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a private IPv4 interface"]
+    async fn notifications_become_events() {
+        let (requests, mut received) = mpsc::unbounded_channel();
+        let speaker = Speaker::from_device(fake_speaker("200 OK", requests).await).unwrap();
+        let mut events = subscribe(&speaker).await;
+
+        let (method, callback) = received.recv().await.unwrap();
+        assert_eq!(method, "SUBSCRIBE");
+        assert_eq!(events.sid(), "uuid:RINCON_000E58000000001400_sub0000000001");
+
+        // properties without a LastChange are skipped
+        notify(
+            &callback,
+            &events.sid(),
+            "<e:property><Foo>1</Foo></e:property>",
+        )
+        .await;
+        notify(
+            &callback,
+            &events.sid(),
+            "<e:property><LastChange>&lt;Event xmlns=&quot;urn:schemas-upnp-org:metadata-1-0/RCS/&quot;&gt;&lt;InstanceID val=&quot;0&quot;&gt;&lt;Volume channel=&quot;Master&quot; val=&quot;23&quot;/&gt;&lt;Volume channel=&quot;LF&quot; val=&quot;100&quot;/&gt;&lt;Mute channel=&quot;Master&quot; val=&quot;1&quot;/&gt;&lt;/InstanceID&gt;&lt;/Event&gt;</LastChange></e:property>",
+        )
+        .await;
+
+        match events.try_next().await.unwrap() {
+            Some(Event::RenderingControl(change)) => {
+                assert_eq!(change.volume(), Some(23));
+                assert_eq!(change.left_volume(), Some(100));
+                assert_eq!(change.mute(), Some(true));
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+
+        let sid = events.sid();
+        drop(events);
+        assert_eq!(
+            received.recv().await.unwrap(),
+            ("UNSUBSCRIBE".to_string(), sid)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    #[ignore = "needs a private IPv4 interface"]
+    async fn rejected_renewal_subscribes_again() {
+        let (requests, mut received) = mpsc::unbounded_channel();
+        let device = fake_speaker("412 Precondition Failed", requests).await;
+        let speaker = Speaker::from_device(device).unwrap();
+        let events = subscribe(&speaker).await;
+        let (method, _) = received.recv().await.unwrap();
+        assert_eq!(method, "SUBSCRIBE");
+
+        // the stream isn't polled, the renewal happens in the background anyway
+        let (method, sid) = received.recv().await.unwrap();
+        assert_eq!(method, "SUBSCRIBE");
+        assert_eq!(sid, "uuid:RINCON_000E58000000001400_sub0000000001");
+
+        let (method, _) = received.recv().await.unwrap();
+        assert_eq!(method, "SUBSCRIBE");
+        let new_sid = "uuid:RINCON_000E58000000001400_sub0000000002";
+        for _ in 0..100 {
+            if events.sid() == new_sid {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(events.sid(), new_sid);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{utils, Result};
-use roxmltree::Node;
+use roxmltree::{Document, Node};
 
 /// A [Track](struct.Track.html) with some metadata like the track number, its duration and the
 /// elapsed time.
@@ -47,6 +47,17 @@ impl TrackInfo {
     }
 }
 
+/// What a speaker is currently playing, as returned by
+/// [Speaker::track](struct.Speaker.html#method.track).
+#[derive(Debug)]
+pub enum CurrentTrack {
+    /// A track from the queue or a stream.
+    Track(TrackInfo),
+    /// The TV input of a home theater speaker, see
+    /// [switch_to_tv](struct.Speaker.html#method.switch_to_tv).
+    Tv,
+}
+
 /// The track struct contains information about the music in UPnP music players.
 /// It always has a title and an URI, but sometimes there is a creator, album or duration specified
 /// too.
@@ -92,6 +103,13 @@ impl std::fmt::Display for Track {
 }
 
 impl Track {
+    /// Parses the first `item` of a DIDL-Lite metadata document.
+    pub(crate) fn from_metadata(metadata: &str) -> Result<Self> {
+        let doc = Document::parse(metadata)?;
+        let item = utils::find_root_node(&doc, "item", "Track Metadata")?;
+        Self::from_xml(item)
+    }
+
     pub(crate) fn from_xml(node: Node<'_, '_>) -> Result<Self> {
         let mut title = None;
         let mut creator = None;
@@ -0,0 +1,695 @@
+use crate::{
+    utils::{self, HashMapExt},
+    RepeatMode, Result,
+};
+use roxmltree::Document;
+use std::{collections::HashMap, convert::Infallible, fmt, ops};
+
+/// What a speaker is playing from, parsed from the scheme of its transport URI.
+/// Formatting it with `Display` gives back the exact URI it was parsed from.
+///
+/// Variants with a single scheme contain the part after it, the others contain the whole URI.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TransportSource {
+    /// The queue of a speaker, `x-rincon-queue:<uuid>#0`.
+    Queue(String),
+    /// The speaker is grouped to the coordinator with the UUID, `x-rincon:<uuid>`.
+    Grouped(String),
+    /// The line-in of the speaker with the UUID, `x-rincon-stream:<uuid>`.
+    LineIn(String),
+    /// The TV input of a home theater speaker, `x-sonos-htastream:<uuid>:spdif`.
+    Tv(String),
+    /// A radio stream like `x-sonosapi-stream:` or `x-rincon-mp3radio:`.
+    Radio(String),
+    /// A track of a music service like `x-sonos-spotify:` or `x-sonos-http:`.
+    MusicService(String),
+    /// A stream controlled by another app, e.g. Spotify Connect or AirPlay, `x-sonos-vli:...`.
+    Vli(String),
+    /// A plain `http://` or `https://` URI.
+    Http(String),
+    /// Any other URI.
+    Other(String),
+}
+
+const QUEUE: &str = "x-rincon-queue:";
+const GROUPED: &str = "x-rincon:";
+const LINE_IN: &str = "x-rincon-stream:";
+const TV: &str = "x-sonos-htastream:";
+const VLI: &str = "x-sonos-vli:";
+const RADIO: &[&str] = &["x-sonosapi-stream:", "x-rincon-mp3radio:"];
+const MUSIC_SERVICE: &[&str] = &["x-sonos-", "x-sonosapi-", "x-sonosprog-"];
+const HTTP: &[&str] = &["http://", "https://"];
+
+impl fmt::Display for TransportSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransportSource::Queue(rest) => write!(f, "{}{}", QUEUE, rest),
+            TransportSource::Grouped(uuid) => write!(f, "{}{}", GROUPED, uuid),
+            TransportSource::LineIn(uuid) => write!(f, "{}{}", LINE_IN, uuid),
+            TransportSource::Tv(rest) => write!(f, "{}{}", TV, rest),
+            TransportSource::Vli(rest) => write!(f, "{}{}", VLI, rest),
+            TransportSource::Radio(uri)
+            | TransportSource::MusicService(uri)
+            | TransportSource::Http(uri)
+            | TransportSource::Other(uri) => f.write_str(uri),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseTransportSourceError;
+impl std::error::Error for ParseTransportSourceError {}
+impl fmt::Display for ParseTransportSourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "provided string was an empty transport uri".fmt(f)
+    }
+}
+
+impl std::str::FromStr for TransportSource {
+    type Err = ParseTransportSourceError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let starts_with_any = |prefixes: &[&str]| prefixes.iter().any(|p| s.starts_with(p));
+
+        let source = if s.is_empty() {
+            return Err(ParseTransportSourceError);
+        } else if let Some(rest) = s.strip_prefix(QUEUE) {
+            TransportSource::Queue(rest.to_string())
+        } else if let Some(uuid) = s.strip_prefix(GROUPED) {
+            TransportSource::Grouped(uuid.to_string())
+        } else if let Some(uuid) = s.strip_prefix(LINE_IN) {
+            TransportSource::LineIn(uuid.to_string())
+        } else if let Some(rest) = s.strip_prefix(TV) {
+            TransportSource::Tv(rest.to_string())
+        } else if let Some(rest) = s.strip_prefix(VLI) {
+            TransportSource::Vli(rest.to_string())
+        } else if starts_with_any(RADIO) {
+            TransportSource::Radio(s.to_string())
+        } else if starts_with_any(MUSIC_SERVICE) {
+            TransportSource::MusicService(s.to_string())
+        } else if starts_with_any(HTTP) {
+            TransportSource::Http(s.to_string())
+        } else {
+            TransportSource::Other(s.to_string())
+        };
+
+        Ok(source)
+    }
+}
+
+/// The playback state of a speaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransportState {
+    /// `PLAYING`
+    Playing,
+    /// `TRANSITIONING`, e.g. while buffering the next track.
+    Transitioning,
+    /// `PAUSED_PLAYBACK`
+    PausedPlayback,
+    /// `STOPPED`
+    Stopped,
+    /// `NO_MEDIA_PRESENT`
+    NoMediaPresent,
+}
+
+impl fmt::Display for TransportState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransportState::Playing => f.write_str("PLAYING"),
+            TransportState::Transitioning => f.write_str("TRANSITIONING"),
+            TransportState::PausedPlayback => f.write_str("PAUSED_PLAYBACK"),
+            TransportState::Stopped => f.write_str("STOPPED"),
+            TransportState::NoMediaPresent => f.write_str("NO_MEDIA_PRESENT"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseTransportStateError;
+impl std::error::Error for ParseTransportStateError {}
+impl fmt::Display for ParseTransportStateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "provided string was not `PLAYING`, `TRANSITIONING`, `PAUSED_PLAYBACK`, `STOPPED` or `NO_MEDIA_PRESENT`".fmt(f)
+    }
+}
+
+impl std::str::FromStr for TransportState {
+    type Err = ParseTransportStateError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "PLAYING" => Ok(TransportState::Playing),
+            "TRANSITIONING" => Ok(TransportState::Transitioning),
+            "PAUSED_PLAYBACK" => Ok(TransportState::PausedPlayback),
+            "STOPPED" => Ok(TransportState::Stopped),
+            "NO_MEDIA_PRESENT" => Ok(TransportState::NoMediaPresent),
+            _ => Err(ParseTransportStateError),
+        }
+    }
+}
+
+/// The set of transport actions which are currently allowed, as returned by
+/// [Speaker::current_transport_actions](struct.Speaker.html#method.current_transport_actions).
+/// Its string representation is a comma separated list like `Play, Stop, Pause, Next`.
+///
+/// Sets can be combined using `|` and checked using [contains](#method.contains).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TransportActions(u16);
+
+#[allow(missing_docs)]
+impl TransportActions {
+    pub const SET: Self = Self(1);
+    pub const PLAY: Self = Self(1 << 1);
+    pub const STOP: Self = Self(1 << 2);
+    pub const PAUSE: Self = Self(1 << 3);
+    pub const SEEK: Self = Self(1 << 4);
+    pub const NEXT: Self = Self(1 << 5);
+    pub const PREVIOUS: Self = Self(1 << 6);
+    /// `X_DLNA_SeekTime`, seeking to a time in the current track.
+    pub const SEEK_TIME: Self = Self(1 << 7);
+    /// `X_DLNA_SeekTrackNr`, seeking to a track in the queue.
+    pub const SEEK_TRACK_NR: Self = Self(1 << 8);
+
+    const NAMES: &'static [(TransportActions, &'static str)] = &[
+        (Self::SET, "Set"),
+        (Self::PLAY, "Play"),
+        (Self::STOP, "Stop"),
+        (Self::PAUSE, "Pause"),
+        (Self::SEEK, "Seek"),
+        (Self::NEXT, "Next"),
+        (Self::PREVIOUS, "Previous"),
+        (Self::SEEK_TIME, "X_DLNA_SeekTime"),
+        (Self::SEEK_TRACK_NR, "X_DLNA_SeekTrackNr"),
+    ];
+
+    /// The set without any actions.
+    pub fn empty() -> Self {
+        Self(0)
+    }
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+    /// Whether all actions in `other` are part of this set.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+    pub fn insert(&mut self, other: Self) -> &mut Self {
+        self.0 |= other.0;
+        self
+    }
+    pub fn remove(&mut self, other: Self) -> &mut Self {
+        self.0 &= !other.0;
+        self
+    }
+}
+
+impl ops::BitOr for TransportActions {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl ops::BitOrAssign for TransportActions {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl fmt::Display for TransportActions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = Self::NAMES
+            .iter()
+            .filter(|(action, _)| self.contains(*action))
+            .map(|(_, name)| name);
+        if let Some(name) = names.next() {
+            f.write_str(name)?;
+        }
+        names.try_for_each(|name| write!(f, ", {}", name))
+    }
+}
+
+impl std::str::FromStr for TransportActions {
+    type Err = Infallible;
+
+    /// Unknown actions are ignored.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut actions = Self::empty();
+        for name in s.split(',').map(str::trim) {
+            if let Some((action, _)) = Self::NAMES
+                .iter()
+                .find(|(_, n)| n.eq_ignore_ascii_case(name))
+            {
+                actions.insert(*action);
+            }
+        }
+        Ok(actions)
+    }
+}
+
+/// The play modes supported by what the speaker is currently playing, as returned by
+/// [Speaker::valid_play_modes](struct.Speaker.html#method.valid_play_modes).
+/// Its string representation is the `CurrentValidPlayModes` of sonos, e.g.
+/// `SHUFFLE,REPEAT,REPEATONE,CROSSFADE`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ValidPlayModes {
+    shuffle: bool,
+    repeat: bool,
+    repeat_one: bool,
+    crossfade: bool,
+}
+
+#[allow(missing_docs)]
+impl ValidPlayModes {
+    pub fn shuffle(&self) -> bool {
+        self.shuffle
+    }
+    /// Whether the whole queue can be repeated.
+    pub fn repeat(&self) -> bool {
+        self.repeat
+    }
+    pub fn repeat_one(&self) -> bool {
+        self.repeat_one
+    }
+    pub fn crossfade(&self) -> bool {
+        self.crossfade
+    }
+    /// Whether `repeat_mode` can be set.
+    pub fn allows_repeat_mode(&self, repeat_mode: RepeatMode) -> bool {
+        match repeat_mode {
+            RepeatMode::None => true,
+            RepeatMode::One => self.repeat_one,
+            RepeatMode::All => self.repeat,
+        }
+    }
+}
+
+impl fmt::Display for ValidPlayModes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modes = [
+            (self.shuffle, "SHUFFLE"),
+            (self.repeat, "REPEAT"),
+            (self.repeat_one, "REPEATONE"),
+            (self.crossfade, "CROSSFADE"),
+        ];
+        let mut modes = modes
+            .iter()
+            .filter(|(valid, _)| *valid)
+            .map(|(_, mode)| mode);
+        if let Some(mode) = modes.next() {
+            f.write_str(mode)?;
+        }
+        modes.try_for_each(|mode| write!(f, ",{}", mode))
+    }
+}
+
+impl std::str::FromStr for ValidPlayModes {
+    type Err = Infallible;
+
+    /// Unknown play modes are ignored.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut modes = Self::default();
+        for mode in s.split(',').map(str::trim) {
+            match mode.to_uppercase().as_str() {
+                "SHUFFLE" => modes.shuffle = true,
+                "REPEAT" => modes.repeat = true,
+                "REPEATONE" | "REPEAT_ONE" => modes.repeat_one = true,
+                "CROSSFADE" => modes.crossfade = true,
+                _ => (),
+            }
+        }
+        Ok(modes)
+    }
+}
+
+/// The transport state together with its status and speed, as returned by
+/// [Speaker::transport_info](struct.Speaker.html#method.transport_info).
+#[derive(Debug, Clone)]
+pub struct TransportInfo {
+    state: TransportState,
+    status: String,
+    speed: String,
+}
+
+#[allow(missing_docs)]
+impl TransportInfo {
+    pub(crate) fn from_response(mut map: HashMap<String, String>) -> Result<Self> {
+        Ok(Self {
+            state: map
+                .extract("CurrentTransportState")?
+                .parse()
+                .map_err(rupnp::Error::invalid_response)?,
+            status: map.extract("CurrentTransportStatus")?,
+            speed: map.remove("CurrentSpeed").unwrap_or_default(),
+        })
+    }
+
+    pub fn state(&self) -> TransportState {
+        self.state
+    }
+    /// `OK` or `ERROR_OCCURRED`
+    pub fn status(&self) -> &str {
+        &self.status
+    }
+    /// Whether the status is `ERROR_OCCURRED`.
+    pub fn is_error(&self) -> bool {
+        self.status.eq_ignore_ascii_case("error_occurred")
+    }
+    /// The playback speed, always `1` for sonos speakers.
+    pub fn speed(&self) -> &str {
+        &self.speed
+    }
+}
+
+/// Information about the media the speaker is playing from, as returned by
+/// [Speaker::media_info](struct.Speaker.html#method.media_info).
+#[derive(Debug, Clone)]
+pub struct MediaInfo {
+    number_of_tracks: u32,
+    media_duration: Option<u32>,
+    current_uri: Option<String>,
+    current_uri_metadata: Option<String>,
+    current_title: Option<String>,
+    next_uri: Option<String>,
+    next_uri_metadata: Option<String>,
+    play_medium: String,
+    write_status: String,
+}
+
+/// Reads the title of the first item or container of a DIDL-Lite metadata document.
+/// Metadata which isn't XML, e.g. `NOT_IMPLEMENTED`, has no title.
+fn metadata_title(metadata: Option<&str>) -> Option<String> {
+    let doc = match Document::parse(metadata?) {
+        Ok(doc) => doc,
+        Err(err) => {
+            log::debug!("skipping uri metadata which isn't XML: {}", err);
+            return None;
+        }
+    };
+    utils::find_root_node(&doc, "title", "URI Metadata")
+        .ok()
+        .and_then(|node| node.text())
+        .map(str::to_string)
+}
+
+#[allow(missing_docs)]
+impl MediaInfo {
+    pub(crate) fn from_response(mut map: HashMap<String, String>) -> Result<Self> {
+        let media_duration = match map.remove("MediaDuration") {
+            Some(duration) if !duration.eq_ignore_ascii_case("not_implemented") => {
+                Some(utils::seconds_from_str(&duration)?)
+            }
+            _ => None,
+        };
+        let current_uri_metadata = map.remove("CurrentURIMetaData");
+
+        Ok(Self {
+            number_of_tracks: map
+                .extract("NrTracks")?
+                .parse()
+                .map_err(rupnp::Error::invalid_response)?,
+            media_duration,
+            current_uri: map.remove("CurrentURI"),
+            current_title: metadata_title(current_uri_metadata.as_deref()),
+            current_uri_metadata,
+            next_uri: map.remove("NextURI"),
+            next_uri_metadata: map.remove("NextURIMetaData"),
+            play_medium: map.remove("PlayMedium").unwrap_or_default(),
+            write_status: map.remove("WriteStatus").unwrap_or_default(),
+        })
+    }
+
+    /// The number of tracks in the queue, `1` for streams.
+    pub fn number_of_tracks(&self) -> u32 {
+        self.number_of_tracks
+    }
+    /// The duration of the whole media in seconds, `None` for streams.
+    pub fn media_duration(&self) -> Option<u32> {
+        self.media_duration
+    }
+    pub fn current_uri(&self) -> Option<&str> {
+        self.current_uri.as_deref()
+    }
+    pub fn current_source(&self) -> Option<TransportSource> {
+        self.current_uri.as_deref().and_then(|uri| uri.parse().ok())
+    }
+    pub fn current_uri_metadata(&self) -> Option<&str> {
+        self.current_uri_metadata.as_deref()
+    }
+    /// The title from the metadata of the current URI, e.g. the name of a radio station or
+    /// playlist.
+    pub fn current_title(&self) -> Option<&str> {
+        self.current_title.as_deref()
+    }
+    pub fn next_uri(&self) -> Option<&str> {
+        self.next_uri.as_deref()
+    }
+    pub fn next_uri_metadata(&self) -> Option<&str> {
+        self.next_uri_metadata.as_deref()
+    }
+    /// e.g. `NETWORK` or `NONE`
+    pub fn play_medium(&self) -> &str {
+        &self.play_medium
+    }
+    pub fn write_status(&self) -> &str {
+        &self.write_status
+    }
+}
+
+/// The storage media and record quality modes of a speaker, as returned by
+/// [Speaker::device_capabilities](struct.Speaker.html#method.device_capabilities).
+/// Sonos speakers only play from the network and can't record, so the record lists are usually
+/// empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceCapabilities {
+    play_media: Vec<String>,
+    record_media: Vec<String>,
+    record_quality_modes: Vec<String>,
+}
+
+/// Splits a comma separated list, treating `NOT_IMPLEMENTED` as empty.
+fn parse_list(list: Option<String>) -> Vec<String> {
+    list.iter()
+        .flat_map(|list| list.split(','))
+        .map(str::trim)
+        .filter(|item| !item.is_empty() && !item.eq_ignore_ascii_case("not_implemented"))
+        .map(str::to_string)
+        .collect()
+}
+
+#[allow(missing_docs)]
+impl DeviceCapabilities {
+    pub(crate) fn from_response(mut map: HashMap<String, String>) -> Self {
+        Self {
+            play_media: parse_list(map.remove("PlayMedia")),
+            record_media: parse_list(map.remove("RecMedia")),
+            record_quality_modes: parse_list(map.remove("RecQualityModes")),
+        }
+    }
+
+    /// e.g. `NONE` and `NETWORK`
+    pub fn play_media(&self) -> &[String] {
+        &self.play_media
+    }
+    pub fn record_media(&self) -> &[String] {
+        &self.record_media
+    }
+    pub fn record_quality_modes(&self) -> &[String] {
+        &self.record_quality_modes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transport_source_round_trip() {
+        let uuid = "RINCON_000E58000000001400";
+        let cases = [
+            (
+                "x-rincon-queue:RINCON_000E58000000001400#0",
+                TransportSource::Queue(format!("{}#0", uuid)),
+            ),
+            (
+                "x-rincon:RINCON_000E58000000001400",
+                TransportSource::Grouped(uuid.to_string()),
+            ),
+            (
+                "x-rincon-stream:RINCON_000E58000000001400",
+                TransportSource::LineIn(uuid.to_string()),
+            ),
+            (
+                "x-sonos-htastream:RINCON_000E58000000001400:spdif",
+                TransportSource::Tv(format!("{}:spdif", uuid)),
+            ),
+            (
+                "x-sonos-vli:RINCON_000E58000000001400:1,spotify:4a6b0c2e9f1d3a5b",
+                TransportSource::Vli(format!("{}:1,spotify:4a6b0c2e9f1d3a5b", uuid)),
+            ),
+            (
+                "x-sonosapi-stream:s24940?sid=254&flags=8224&sn=0",
+                TransportSource::Radio("x-sonosapi-stream:s24940?sid=254&flags=8224&sn=0".into()),
+            ),
+            (
+                "x-rincon-mp3radio://stream.example.com/live.mp3",
+                TransportSource::Radio("x-rincon-mp3radio://stream.example.com/live.mp3".into()),
+            ),
+            (
+                "x-sonos-spotify:spotify%3atrack%3a2TpxZ7JUBn3uw46aR7qd6V?sid=9&flags=8224&sn=1",
+                TransportSource::MusicService(
+                    "x-sonos-spotify:spotify%3atrack%3a2TpxZ7JUBn3uw46aR7qd6V?sid=9&flags=8224&sn=1"
+                        .into(),
+                ),
+            ),
+            (
+                "x-sonosapi-hls-static:ALkSOiHmMzLvLxiBnH9L?sid=284&flags=8&sn=2",
+                TransportSource::MusicService(
+                    "x-sonosapi-hls-static:ALkSOiHmMzLvLxiBnH9L?sid=284&flags=8&sn=2".into(),
+                ),
+            ),
+            (
+                "x-sonosprog-http:song%3a1440818664.mp4?sid=204&flags=8224&sn=3",
+                TransportSource::MusicService(
+                    "x-sonosprog-http:song%3a1440818664.mp4?sid=204&flags=8224&sn=3".into(),
+                ),
+            ),
+            (
+                "http://192.168.1.10:8000/music/track.mp3",
+                TransportSource::Http("http://192.168.1.10:8000/music/track.mp3".into()),
+            ),
+            (
+                "https://example.com/podcast/episode-1.mp3",
+                TransportSource::Http("https://example.com/podcast/episode-1.mp3".into()),
+            ),
+            (
+                "x-file-cifs://nas/music/Daft%20Punk/Discovery/03%20Digital%20Love.flac",
+                TransportSource::Other(
+                    "x-file-cifs://nas/music/Daft%20Punk/Discovery/03%20Digital%20Love.flac".into(),
+                ),
+            ),
+            (
+                "x-rincon-buzzer:0",
+                TransportSource::Other("x-rincon-buzzer:0".into()),
+            ),
+        ];
+
+        for (uri, source) in cases.iter() {
+            let parsed: TransportSource = uri.parse().unwrap();
+            assert_eq!(&parsed, source);
+            assert_eq!(parsed.to_string(), *uri);
+        }
+    }
+
+    fn response(values: &[(&str, &str)]) -> HashMap<String, String> {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn media_info_queue() {
+        let media_info = MediaInfo::from_response(response(&[
+            ("NrTracks", "12"),
+            ("MediaDuration", "NOT_IMPLEMENTED"),
+            ("CurrentURI", "x-rincon-queue:RINCON_000E58000000001400#0"),
+            ("PlayMedium", "NETWORK"),
+            ("WriteStatus", "NOT_IMPLEMENTED"),
+        ]))
+        .unwrap();
+
+        assert_eq!(media_info.number_of_tracks(), 12);
+        assert_eq!(media_info.media_duration(), None);
+        assert_eq!(
+            media_info.current_source(),
+            Some(TransportSource::Queue(
+                "RINCON_000E58000000001400#0".to_string()
+            ))
+        );
+        assert_eq!(media_info.current_uri_metadata(), None);
+        assert_eq!(media_info.current_title(), None);
+        assert_eq!(media_info.next_uri(), None);
+        assert_eq!(media_info.play_medium(), "NETWORK");
+    }
+
+    #[test]
+    fn media_info_radio() {
+        let media_info = MediaInfo::from_response(response(&[
+            ("NrTracks", "1"),
+            ("MediaDuration", "NOT_IMPLEMENTED"),
+            (
+                "CurrentURI",
+                "x-sonosapi-stream:s24940?sid=254&flags=8224&sn=0",
+            ),
+            (
+                "CurrentURIMetaData",
+                concat!(
+                    r#"<DIDL-Lite xmlns:dc="http://purl.org/dc/elements/1.1/" "#,
+                    r#"xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/" "#,
+                    r#"xmlns:r="urn:schemas-rinconnetworks-com:metadata-1-0/" "#,
+                    r#"xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/">"#,
+                    r#"<item id="-1" parentID="-1" restricted="true">"#,
+                    r#"<dc:title>BBC Radio 6 Music</dc:title>"#,
+                    r#"<upnp:class>object.item.audioItem.audioBroadcast</upnp:class>"#,
+                    r#"<desc id="cdudn" nameSpace="urn:schemas-rinconnetworks-com:metadata-1-0/">"#,
+                    r#"SA_RINCON65031_</desc></item></DIDL-Lite>"#
+                ),
+            ),
+            ("PlayMedium", "NETWORK"),
+            ("WriteStatus", "NOT_IMPLEMENTED"),
+        ]))
+        .unwrap();
+
+        assert!(matches!(
+            media_info.current_source(),
+            Some(TransportSource::Radio(_))
+        ));
+        assert_eq!(media_info.current_title(), Some("BBC Radio 6 Music"));
+    }
+
+    #[test]
+    fn media_info_stream() {
+        let media_info = MediaInfo::from_response(response(&[
+            ("NrTracks", "1"),
+            ("MediaDuration", "0:03:20"),
+            ("CurrentURI", "http://192.168.1.10:8000/music/track.mp3"),
+            ("CurrentURIMetaData", "NOT_IMPLEMENTED"),
+            ("NextURI", "http://192.168.1.10:8000/music/next.mp3"),
+            ("PlayMedium", "NETWORK"),
+            ("WriteStatus", "NOT_IMPLEMENTED"),
+        ]))
+        .unwrap();
+
+        assert_eq!(media_info.media_duration(), Some(200));
+        assert!(matches!(
+            media_info.current_source(),
+            Some(TransportSource::Http(_))
+        ));
+        assert_eq!(media_info.current_uri_metadata(), Some("NOT_IMPLEMENTED"));
+        assert_eq!(media_info.current_title(), None);
+        assert_eq!(
+            media_info.next_uri(),
+            Some("http://192.168.1.10:8000/music/next.mp3")
+        );
+    }
+
+    #[test]
+    fn device_capabilities() {
+        let capabilities = DeviceCapabilities::from_response(response(&[
+            ("PlayMedia", "NONE,NETWORK"),
+            ("RecMedia", "NOT_IMPLEMENTED"),
+            ("RecQualityModes", "NOT_IMPLEMENTED"),
+        ]));
+
+        assert_eq!(capabilities.play_media(), ["NONE", "NETWORK"]);
+        assert!(capabilities.record_media().is_empty());
+        assert!(capabilities.record_quality_modes().is_empty());
+    }
+
+    #[test]
+    fn empty_transport_source() {
+        assert!("".parse::<TransportSource>().is_err());
+    }
+}
@@ -49,8 +49,24 @@ pub fn seconds_from_str(s: &str) -> Result<u32> {
     opt.ok_or(rupnp::Error::ParseError("invalid duration").into())
 }
 
-pub fn parse_bool(s: String) -> Result<bool> {
-    match s.trim() {
+/// Escapes a string so it can be used as the value of an action argument.
+pub fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub fn parse_bool(s: impl AsRef<str>) -> Result<bool> {
+    match s.as_ref().trim() {
         "0" => Ok(false),
         "1" => Ok(true),
         _ => Err(rupnp::Error::ParseError("bool was neither `0` nor `1`").into()),
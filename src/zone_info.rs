@@ -0,0 +1,104 @@
+use crate::{utils::HashMapExt, Result};
+use std::{collections::HashMap, net::IpAddr};
+
+/// Hardware and firmware information about a speaker, as returned by
+/// [Speaker::zone_info](struct.Speaker.html#method.zone_info).
+#[derive(Debug, Clone)]
+pub struct ZoneInfo {
+    serial_number: String,
+    software_version: String,
+    display_software_version: String,
+    hardware_version: String,
+    ip_address: IpAddr,
+    mac_address: String,
+    copyright_info: String,
+    extra_info: String,
+    ht_audio_in: u32,
+    flags: u32,
+}
+
+#[allow(missing_docs)]
+impl ZoneInfo {
+    pub(crate) fn from_response(mut map: HashMap<String, String>) -> Result<Self> {
+        Ok(Self {
+            serial_number: map.extract("SerialNumber")?,
+            software_version: map.extract("SoftwareVersion")?,
+            display_software_version: map.extract("DisplaySoftwareVersion")?,
+            hardware_version: map.extract("HardwareVersion")?,
+            ip_address: map
+                .extract("IPAddress")?
+                .parse()
+                .map_err(rupnp::Error::invalid_response)?,
+            mac_address: map.extract("MACAddress")?,
+            copyright_info: map.remove("CopyrightInfo").unwrap_or_default(),
+            extra_info: map.remove("ExtraInfo").unwrap_or_default(),
+            ht_audio_in: map
+                .extract("HTAudioIn")?
+                .parse()
+                .map_err(rupnp::Error::invalid_response)?,
+            flags: map
+                .extract("Flags")?
+                .parse()
+                .map_err(rupnp::Error::invalid_response)?,
+        })
+    }
+
+    /// e.g. `00-0E-58-12-34-56:7`
+    pub fn serial_number(&self) -> &str {
+        &self.serial_number
+    }
+    /// The internal firmware version, e.g. `56.0-76060`.
+    pub fn software_version(&self) -> &str {
+        &self.software_version
+    }
+    /// The firmware version as shown in the app, e.g. `11.2`.
+    pub fn display_software_version(&self) -> &str {
+        &self.display_software_version
+    }
+    pub fn hardware_version(&self) -> &str {
+        &self.hardware_version
+    }
+    pub fn ip_address(&self) -> IpAddr {
+        self.ip_address
+    }
+    pub fn mac_address(&self) -> &str {
+        &self.mac_address
+    }
+    pub fn copyright_info(&self) -> &str {
+        &self.copyright_info
+    }
+    pub fn extra_info(&self) -> &str {
+        &self.extra_info
+    }
+    pub fn ht_audio_in(&self) -> u32 {
+        self.ht_audio_in
+    }
+    pub fn flags(&self) -> u32 {
+        self.flags
+    }
+}
+
+/// A summary of the speaker hardware, combining the model from the device description with its
+/// [ZoneInfo](struct.ZoneInfo.html).
+/// It is returned by [Speaker::hardware](struct.Speaker.html#method.hardware).
+#[derive(Debug, Clone)]
+pub struct Hardware {
+    pub(crate) model_name: String,
+    pub(crate) model_number: Option<String>,
+    pub(crate) zone_info: ZoneInfo,
+}
+
+#[allow(missing_docs)]
+impl Hardware {
+    /// e.g. `Sonos Play:5`
+    pub fn model_name(&self) -> &str {
+        &self.model_name
+    }
+    /// e.g. `S5`
+    pub fn model_number(&self) -> Option<&str> {
+        self.model_number.as_deref()
+    }
+    pub fn zone_info(&self) -> &ZoneInfo {
+        &self.zone_info
+    }
+}